@@ -28,6 +28,18 @@ struct Args {
     #[arg(long, default_value_t = 5)]
     max_media_upload_size_mb: u16,
 
+    /// Max size of an LDK log message (in bytes), longer messages get truncated
+    #[arg(long, default_value_t = 8192)]
+    max_log_line_bytes: usize,
+
+    /// Path for the logs directory (defaults to a directory inside the storage directory)
+    #[arg(long)]
+    logs_dir: Option<PathBuf>,
+
+    /// Prefix of the environment variables used as config fallback (e.g. RLN_INDEXER_URL)
+    #[arg(long, default_value = "RLN_")]
+    env_prefix: String,
+
     /// Root public key for biscuit token authentication (hex-encoded)
     #[arg(long)]
     root_public_key: Option<String>,
@@ -43,6 +55,9 @@ pub(crate) struct UserArgs {
     pub(crate) ldk_peer_listening_port: u16,
     pub(crate) network: BitcoinNetwork,
     pub(crate) max_media_upload_size_mb: u16,
+    pub(crate) max_log_line_bytes: usize,
+    pub(crate) logs_dir: Option<PathBuf>,
+    pub(crate) env_prefix: String,
     pub(crate) root_public_key: Option<biscuit_auth::PublicKey>,
 }
 
@@ -64,6 +79,9 @@ pub(crate) fn parse_startup_args() -> Result<UserArgs, AppError> {
         ldk_peer_listening_port,
         network,
         max_media_upload_size_mb: args.max_media_upload_size_mb,
+        max_log_line_bytes: args.max_log_line_bytes,
+        logs_dir: args.logs_dir,
+        env_prefix: args.env_prefix,
         root_public_key,
     })
 }
@@ -10,13 +10,38 @@ use std::{
 use tempfile::NamedTempFile;
 
 use crate::{
+    disk::map_write_error,
     error::{APIError, AppError, AuthError},
     utils::{hex_str, hex_str_to_vec, AppState},
 };
 
 const REVOKED_TOKENS_FILE: &str = "revoked_tokens.txt";
 
-const READ_ONLY_OPS: [&str; 23] = [
+/// Revocation identifier of a Biscuit token
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct RevocationId(Vec<u8>);
+
+impl RevocationId {
+    /// Parse a revocation ID from a non-empty, even-length hex string
+    pub(crate) fn from_hex(hex: &str) -> Option<Self> {
+        if hex.is_empty() || hex.len() % 2 != 0 {
+            return None;
+        }
+        hex_str_to_vec(hex).map(Self)
+    }
+
+    pub(crate) fn to_hex(&self) -> String {
+        hex_str(&self.0)
+    }
+}
+
+impl From<Vec<u8>> for RevocationId {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+const READ_ONLY_OPS: [&str; 24] = [
     "/assetbalance",
     "/assetmetadata",
     "/btcbalance",
@@ -25,6 +50,7 @@ const READ_ONLY_OPS: [&str; 23] = [
     "/decodelninvoice",
     "/decodergbinvoice",
     "/estimatefee",
+    "/exportpayments",
     "/getassetmedia",
     "/getchannelid",
     "/getpayment",
@@ -187,12 +213,12 @@ impl AppState {
         let file_body = {
             let mut revoked = self.revoked_tokens.lock().unwrap();
             for id in revocation_ids {
-                revoked.insert(id);
+                revoked.insert(RevocationId::from(id));
             }
 
             let mut updated_list = String::new();
             for token_id in revoked.iter() {
-                updated_list.push_str(&hex_str(token_id));
+                updated_list.push_str(&token_id.to_hex());
                 updated_list.push('\n');
             }
             updated_list
@@ -219,7 +245,7 @@ impl AppState {
                     "Failed to write/flush/sync temporary revoked-tokens file: {}",
                     e
                 );
-                APIError::IO(e)
+                map_write_error(e, &path)
             })?;
 
         // atomically replace the destination file with the synced temp file
@@ -237,7 +263,11 @@ impl AppState {
     }
 
     fn is_token_revoked(&self, token: &Biscuit) -> bool {
-        let revocation_ids: HashSet<_> = token.revocation_identifiers().into_iter().collect();
+        let revocation_ids: HashSet<_> = token
+            .revocation_identifiers()
+            .into_iter()
+            .map(RevocationId::from)
+            .collect();
         let revoked = self.revoked_tokens.lock().unwrap();
         !revocation_ids.is_disjoint(&*revoked)
     }
@@ -246,7 +276,7 @@ impl AppState {
         self.static_state.storage_dir_path.join(REVOKED_TOKENS_FILE)
     }
 
-    pub(crate) fn load_revoked_tokens(&self) -> Result<HashSet<Vec<u8>>, AppError> {
+    pub(crate) fn load_revoked_tokens(&self) -> Result<HashSet<RevocationId>, AppError> {
         let path = self.get_revoked_tokens_path();
 
         let file = match fs::File::open(&path) {
@@ -268,7 +298,7 @@ impl AppState {
             }
         };
 
-        let mut revoked: HashSet<Vec<u8>> = HashSet::new();
+        let mut revoked: HashSet<RevocationId> = HashSet::new();
         let reader = BufReader::new(file);
         for (lineno, line_res) in reader.lines().enumerate() {
             let line = line_res.map_err(|e| {
@@ -284,7 +314,7 @@ impl AppState {
             if s.is_empty() || s.starts_with('#') {
                 continue;
             }
-            match hex_str_to_vec(s) {
+            match RevocationId::from_hex(s) {
                 Some(token_id) => {
                     revoked.insert(token_id);
                 }
@@ -14,7 +14,7 @@ use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 
 use crate::error::APIError;
-use crate::utils::LOGS_DIR;
+use crate::utils::{LOGS_DIR, NODE_LOCK_FNAME};
 
 const BACKUP_BUFFER_LEN_ENCRYPT: usize = 239; // 255 max, leaving 16 for the checksum
 const BACKUP_BUFFER_LEN_DECRYPT: usize = BACKUP_BUFFER_LEN_ENCRYPT + 16;
@@ -170,7 +170,7 @@ fn _zip_dir(path_in: &Path, path_out: &Path) -> Result<(), APIError> {
             .to_str()
             .ok_or_else(|| APIError::Unexpected(s!("Failed to convert file name to string")))?;
         if path.is_file() {
-            if path.ends_with("log") {
+            if path.ends_with("log") || path.ends_with(NODE_LOCK_FNAME) {
                 continue;
             }
             tracing::debug!("adding file {path:?} as {name:?}");
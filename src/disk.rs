@@ -1,26 +1,34 @@
+use amplify::s;
 use bitcoin::secp256k1::PublicKey;
 use bitcoin::Network;
-use chrono::Utc;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use lightning::ln::msgs::SocketAddress;
 use lightning::routing::scoring::{ProbabilisticScorer, ProbabilisticScoringDecayParameters};
 use lightning::util::hash_tables::new_hash_map;
 use lightning::util::logger::{Logger, Record};
+use lightning::util::persist::KVStoreSync;
 use lightning::util::ser::{Readable, ReadableArgs, Writer};
+use lightning_persister::fs_store::FilesystemStore;
 use std::collections::HashMap;
 use std::fs;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::net::SocketAddr;
+use std::io::{BufRead, BufReader, ErrorKind, Read};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use tokio::sync::{Mutex as TokioMutex, OwnedMutexGuard};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
 
-use crate::error::APIError;
+use crate::error::{APIError, AppError};
 use crate::ldk::{
     ChannelIdsMap, InboundPaymentInfoStorage, NetworkGraph, OutboundPaymentInfoStorage,
-    OutputSpenderTxes, SwapMap,
+    OutputSpenderTxes, SwapMap, SwapRole,
 };
-use crate::utils::{parse_peer_info, LOGS_DIR};
+use crate::utils::{hex_str_to_compressed_pubkey, LOGS_DIR};
 
 pub(crate) const LDK_LOGS_FILE: &str = "logs.txt";
+const LDK_LOG_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
 
 pub(crate) const INBOUND_PAYMENTS_FNAME: &str = "inbound_payments";
 pub(crate) const OUTBOUND_PAYMENTS_FNAME: &str = "outbound_payments";
@@ -33,81 +41,318 @@ pub(crate) const CHANNEL_IDS_FNAME: &str = "channel_ids";
 
 pub(crate) const MAKER_SWAPS_FNAME: &str = "maker_swaps";
 pub(crate) const TAKER_SWAPS_FNAME: &str = "taker_swaps";
+pub(crate) const NODE_ALIAS_FNAME: &str = "node_alias";
+pub(crate) const NODE_COLOR_FNAME: &str = "node_color";
+
+pub(crate) const NETWORK_GRAPH_FNAME: &str = "network_graph";
+pub(crate) const SCORER_FNAME: &str = "scorer";
+
+const SCORER_SNAPSHOT_MAGIC: &[u8; 4] = b"RLNS";
+const SCORER_SNAPSHOT_VERSION: u8 = 1;
+
+static FILE_STATE_LOCKS: LazyLock<Mutex<HashMap<PathBuf, Arc<TokioMutex<()>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Exclusive access to a flat-file state path, held across a read-modify-write cycle.
+///
+/// Guards for the same file serialize, guards for different files don't contend.
+pub(crate) struct FileStateGuard {
+    _guard: OwnedMutexGuard<()>,
+}
+
+impl FileStateGuard {
+    pub(crate) async fn acquire(path: &Path) -> Self {
+        // canonicalizing hits the filesystem, don't hold the registry lock while doing it
+        let canonical_path = _canonical_state_path(path);
+        let lock = FILE_STATE_LOCKS
+            .lock()
+            .unwrap()
+            .entry(canonical_path)
+            .or_default()
+            .clone();
+        Self {
+            _guard: lock.lock_owned().await,
+        }
+    }
+}
+
+fn _canonical_state_path(path: &Path) -> PathBuf {
+    // the file may not exist yet, so only canonicalize its parent directory
+    if let (Some(parent), Some(file_name)) = (path.parent(), path.file_name()) {
+        let parent = if parent.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            parent
+        };
+        if let Ok(canonical_parent) = fs::canonicalize(parent) {
+            return canonical_parent.join(file_name);
+        }
+    }
+    path.to_path_buf()
+}
 
 pub(crate) struct FilesystemLogger {
     data_dir: PathBuf,
+    max_line_bytes: usize,
+    lines_written: AtomicU64,
+    bytes_written: AtomicU64,
+    dropped_lines: AtomicU64,
+}
+
+/// Snapshot of the [`FilesystemLogger`] throughput counters.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct LoggerStats {
+    pub(crate) lines_written: u64,
+    pub(crate) bytes_written: u64,
+    pub(crate) dropped_lines: u64,
 }
 
 impl FilesystemLogger {
-    pub(crate) fn new(data_dir: PathBuf) -> Self {
-        let logs_path = data_dir.join(LOGS_DIR);
-        fs::create_dir_all(logs_path.clone()).unwrap();
-        Self {
-            data_dir: logs_path,
+    pub(crate) fn new(data_dir: PathBuf, max_line_bytes: usize) -> Result<Self, AppError> {
+        Self::new_with_logs_dir(data_dir.join(LOGS_DIR), max_line_bytes)
+    }
+
+    /// Create a logger writing to the given logs directory, checking it can be written to.
+    pub(crate) fn new_with_logs_dir(
+        logs_dir: PathBuf,
+        max_line_bytes: usize,
+    ) -> Result<Self, AppError> {
+        fs::create_dir_all(&logs_dir)
+            .and_then(|_| {
+                fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(logs_dir.join(LDK_LOGS_FILE))
+            })
+            .map_err(|e| AppError::UnwritableLogsDir(logs_dir.clone(), e.to_string()))?;
+        Ok(Self {
+            data_dir: logs_dir,
+            max_line_bytes,
+            lines_written: AtomicU64::new(0),
+            bytes_written: AtomicU64::new(0),
+            dropped_lines: AtomicU64::new(0),
+        })
+    }
+
+    pub(crate) fn logs_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    pub(crate) fn stats(&self) -> LoggerStats {
+        LoggerStats {
+            lines_written: self.lines_written.load(Ordering::Relaxed),
+            bytes_written: self.bytes_written.load(Ordering::Relaxed),
+            dropped_lines: self.dropped_lines.load(Ordering::Relaxed),
         }
     }
 }
 
+/// Daily rolling appender for the node logs, reporting an unusable logs directory instead of
+/// panicking.
+pub(crate) fn daily_file_appender(logs_dir: &Path) -> Result<RollingFileAppender, AppError> {
+    RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("rln.log")
+        .build(logs_dir)
+        .map_err(|e| AppError::UnwritableLogsDir(logs_dir.to_path_buf(), e.to_string()))
+}
+
+/// Truncate a log message longer than `max_bytes`, appending a marker with the dropped size.
+pub(crate) fn truncate_log_message(mut message: String, max_bytes: usize) -> String {
+    if message.len() <= max_bytes {
+        return message;
+    }
+    let mut end = max_bytes;
+    while !message.is_char_boundary(end) {
+        end -= 1;
+    }
+    let truncated_bytes = message.len() - end;
+    message.truncate(end);
+    message.push_str(&format!("\u{2026}[truncated {truncated_bytes} bytes]"));
+    message
+}
+
 impl Logger for FilesystemLogger {
     fn log(&self, record: Record) {
-        let raw_log = record.args.to_string();
+        let raw_log = truncate_log_message(record.args.to_string(), self.max_line_bytes);
         let log = format!(
             "{} {:<5} [{}:{}] {}\n",
             // Note that a "real" lightning node almost certainly does *not* want subsecond
             // precision for message-receipt information as it makes log entries a target for
             // deanonymization attacks. For testing, however, its quite useful.
-            Utc::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            Utc::now().format(LDK_LOG_TIMESTAMP_FORMAT),
             record.level.to_string(),
             record.module_path,
             record.line,
             raw_log
         );
         let logs_file_path = self.data_dir.join(LDK_LOGS_FILE);
-        fs::OpenOptions::new()
+        let res = fs::OpenOptions::new()
             .create(true)
             .append(true)
-            .open(logs_file_path)
-            .unwrap()
-            .write_all(log.as_bytes())
-            .unwrap();
+            .open(&logs_file_path)
+            .and_then(|mut file| file.write_all(log.as_bytes()));
+        // a log line that cannot be written (e.g. disk full) is dropped, not fatal
+        if let Err(e) = res {
+            self.dropped_lines.fetch_add(1, Ordering::Relaxed);
+            tracing::warn!(
+                "Failed to write LDK log to {}: {}",
+                logs_file_path.display(),
+                map_write_error(e, &logs_file_path)
+            );
+        } else {
+            self.lines_written.fetch_add(1, Ordering::Relaxed);
+            self.bytes_written
+                .fetch_add(log.len() as u64, Ordering::Relaxed);
+        }
+    }
+}
+
+fn _log_line_timestamp(line: &str) -> Option<DateTime<Utc>> {
+    // the timestamp format has a fixed length
+    let timestamp = line.get(..23)?;
+    NaiveDateTime::parse_from_str(timestamp, LDK_LOG_TIMESTAMP_FORMAT)
+        .ok()
+        .map(|t| t.and_utc())
+}
+
+/// Read the LDK log lines written between `start` and `end` (both included), from the current
+/// log file and any rotated one.
+///
+/// Lines without a timestamp, such as the continuation of a multi-line message, are attributed
+/// to the preceding timestamped line.
+pub(crate) fn read_logs_between(
+    logs_dir: &Path,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<String>, APIError> {
+    let mut entries: Vec<(DateTime<Utc>, Vec<String>)> = vec![];
+    for dir_entry in fs::read_dir(logs_dir)? {
+        let path = dir_entry?.path();
+        let is_log_file = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with(LDK_LOGS_FILE));
+        if !is_log_file {
+            continue;
+        }
+        let mut in_window = false;
+        for line in BufReader::new(File::open(&path)?).lines() {
+            let line = line?;
+            match _log_line_timestamp(&line) {
+                Some(timestamp) => {
+                    in_window = timestamp >= start && timestamp <= end;
+                    if in_window {
+                        entries.push((timestamp, vec![line]));
+                    }
+                }
+                None if in_window => entries.last_mut().unwrap().1.push(line),
+                None => {}
+            }
+        }
+    }
+    // rotated files hold older lines, keep the entries of all files in chronological order
+    entries.sort_by_key(|(timestamp, _)| *timestamp);
+    Ok(entries.into_iter().flat_map(|(_, lines)| lines).collect())
+}
+
+/// Map an error hit while writing to the given path, reporting a full disk as
+/// [`APIError::DiskFull`].
+pub(crate) fn map_write_error(e: std::io::Error, path: &Path) -> APIError {
+    if e.kind() == ErrorKind::StorageFull {
+        APIError::DiskFull(path.to_path_buf())
+    } else {
+        APIError::IO(e)
     }
 }
 
-pub(crate) fn persist_channel_peer(
+pub(crate) fn write_file(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), APIError> {
+    fs::write(path, contents).map_err(|e| map_write_error(e, path))
+}
+
+/// Persist a flat-file state object through the given store, reporting a full disk as
+/// [`APIError::DiskFull`].
+pub(crate) fn write_state(
+    fs_store: &FilesystemStore,
+    key: &str,
+    contents: Vec<u8>,
+) -> Result<(), APIError> {
+    fs_store
+        .write("", "", key, contents)
+        .map_err(|e| map_write_error(e, &fs_store.get_data_dir().join(key)))
+}
+
+/// Read a text file, returning `None` if it doesn't exist.
+pub(crate) fn read_file_if_exists(path: &Path) -> Result<Option<String>, APIError> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(APIError::IO(e)),
+    }
+}
+
+/// Write the given contents to a file only if they differ from the current ones, to avoid
+/// needless disk writes. Returns whether the file has been written.
+pub(crate) fn write_file_if_changed(
+    path: &Path,
+    contents: impl AsRef<[u8]>,
+) -> Result<bool, APIError> {
+    let contents = contents.as_ref();
+    if fs::read(path).is_ok_and(|current| current == contents) {
+        return Ok(false);
+    }
+    write_file(path, contents)?;
+    Ok(true)
+}
+
+pub(crate) async fn persist_channel_peer(
     path: &Path,
     pubkey: &PublicKey,
-    address: &SocketAddr,
+    address: &SocketAddress,
+) -> Result<(), APIError> {
+    persist_channel_peers(path, &[(*pubkey, address.clone())]).await
+}
+
+pub(crate) async fn persist_channel_peers(
+    path: &Path,
+    peers: &[(PublicKey, SocketAddress)],
 ) -> Result<(), APIError> {
-    let pubkey = pubkey.to_string();
-    let peer_info = if path.exists() {
-        let mut updated_peer_info = fs::read_to_string(path)?
+    let _file_guard = FileStateGuard::acquire(path).await;
+    // deduplicate by pubkey, the last address for a given peer wins
+    let mut new_peers: Vec<(String, &SocketAddress)> = vec![];
+    for (pubkey, address) in peers {
+        let pubkey = pubkey.to_string();
+        new_peers.retain(|(pk, _)| *pk != pubkey);
+        new_peers.push((pubkey, address));
+    }
+    let mut peer_lines = if path.exists() {
+        fs::read_to_string(path)?
             .lines()
-            .filter(|&line| !line.trim().starts_with(&pubkey))
-            .map(|line| line.trim())
+            .map(|line| line.trim().to_string())
+            .filter(|line| {
+                !line.is_empty() && !new_peers.iter().any(|(pk, _)| line.starts_with(pk))
+            })
             .collect::<Vec<_>>()
-            .join("\n");
-        updated_peer_info += format!(
-            "{}{pubkey}@{address}",
-            if updated_peer_info.is_empty() {
-                ""
-            } else {
-                "\n"
-            }
-        )
-        .as_str();
-        updated_peer_info
     } else {
-        format!("{pubkey}@{address}")
+        vec![]
     };
+    peer_lines.extend(
+        new_peers
+            .iter()
+            .map(|(pubkey, address)| format!("{pubkey}@{address}")),
+    );
     let mut tmp_path = path.to_path_buf();
     tmp_path.set_extension("ptmp");
-    fs::write(&tmp_path, peer_info.to_string().as_bytes())?;
+    write_file(&tmp_path, peer_lines.join("\n"))?;
     fs::rename(tmp_path, path)?;
-    tracing::info!("persisted peer (pubkey: {pubkey}, addr: {address})");
+    for (pubkey, address) in new_peers {
+        tracing::info!("persisted peer (pubkey: {pubkey}, addr: {address})");
+    }
     Ok(())
 }
 
-pub(crate) fn delete_channel_peer(path: &Path, pubkey: String) -> Result<(), APIError> {
+pub(crate) async fn delete_channel_peer(path: &Path, pubkey: String) -> Result<(), APIError> {
+    let _file_guard = FileStateGuard::acquire(path).await;
     if path.exists() {
         let updated_peer_info = fs::read_to_string(path)?
             .lines()
@@ -117,15 +362,16 @@ pub(crate) fn delete_channel_peer(path: &Path, pubkey: String) -> Result<(), API
             .join("\n");
         let mut tmp_path = path.to_path_buf();
         tmp_path.set_extension("dtmp");
-        fs::write(&tmp_path, updated_peer_info.to_string().as_bytes())?;
+        write_file(&tmp_path, updated_peer_info)?;
         fs::rename(tmp_path, path)?;
     }
     Ok(())
 }
 
-pub(crate) fn read_channel_peer_data(
+pub(crate) async fn read_channel_peer_data(
     path: &Path,
-) -> Result<HashMap<PublicKey, SocketAddr>, APIError> {
+) -> Result<HashMap<PublicKey, SocketAddress>, APIError> {
+    let _file_guard = FileStateGuard::acquire(path).await;
     let mut peer_data = HashMap::new();
     if !path.exists() {
         return Ok(HashMap::new());
@@ -133,16 +379,34 @@ pub(crate) fn read_channel_peer_data(
     let file = File::open(path)?;
     let reader = BufReader::new(file);
     for line in reader.lines() {
-        match parse_peer_info(line.unwrap()) {
-            Ok((pubkey, socket_addr)) => {
-                peer_data.insert(pubkey, socket_addr.expect("saved info with address"));
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        // skip malformed rows (e.g. from manual edits) instead of failing the whole load
+        match _parse_channel_peer(&line) {
+            Ok((pubkey, address)) => {
+                peer_data.insert(pubkey, address);
             }
-            Err(e) => return Err(e),
+            Err(e) => tracing::warn!("Skipping invalid channel peer entry {line:?}: {e}"),
         }
     }
     Ok(peer_data)
 }
 
+fn _parse_channel_peer(line: &str) -> Result<(PublicKey, SocketAddress), APIError> {
+    let (pubkey, address) = line
+        .trim()
+        .split_once('@')
+        .ok_or_else(|| APIError::InvalidPeerInfo(s!("missing address for saved peer")))?;
+    let pubkey = hex_str_to_compressed_pubkey(pubkey)
+        .ok_or_else(|| APIError::InvalidPeerInfo(s!("unable to parse given pubkey for node")))?;
+    // parsed without resolving, supporting IPv4, IPv6, hostnames and onion v3 addresses
+    let address = SocketAddress::from_str(address)
+        .map_err(|e| APIError::InvalidPeerInfo(format!("unable to parse peer address: {e}")))?;
+    Ok((pubkey, address))
+}
+
 pub(crate) fn read_network(
     path: &Path,
     network: Network,
@@ -187,13 +451,14 @@ pub(crate) fn read_output_spender_txes(path: &Path) -> OutputSpenderTxes {
     new_hash_map()
 }
 
-pub(crate) fn read_swaps_info(path: &Path) -> SwapMap {
-    if let Ok(file) = File::open(path) {
-        if let Ok(info) = SwapMap::read(&mut BufReader::new(file)) {
+pub(crate) fn read_swaps_info(ldk_data_dir: &Path, role: SwapRole) -> SwapMap {
+    if let Ok(file) = File::open(ldk_data_dir.join(role.fname())) {
+        if let Ok(info) = SwapMap::read(&mut BufReader::new(file), role) {
             return info;
         }
     }
     SwapMap {
+        role,
         swaps: new_hash_map(),
     }
 }
@@ -213,6 +478,60 @@ pub(crate) fn read_scorer(
     ProbabilisticScorer::new(params, graph, logger)
 }
 
+/// Write a snapshot of the persisted scorer, prefixed by a magic and version header.
+pub(crate) fn export_scorer(path: &Path, w: &mut impl std::io::Write) -> Result<(), APIError> {
+    let scorer = fs::read(path)?;
+    w.write_all(SCORER_SNAPSHOT_MAGIC)?;
+    w.write_all(&[SCORER_SNAPSHOT_VERSION])?;
+    w.write_all(&scorer)?;
+    Ok(())
+}
+
+/// Replace the persisted scorer with a snapshot written by [`export_scorer`].
+///
+/// The existing file is only overwritten once the snapshot deserializes against the given graph.
+pub(crate) fn import_scorer(
+    path: &Path,
+    r: &mut impl Read,
+    graph: Arc<NetworkGraph>,
+    logger: Arc<FilesystemLogger>,
+) -> Result<(), APIError> {
+    let mut header = [0; SCORER_SNAPSHOT_MAGIC.len() + 1];
+    r.read_exact(&mut header)
+        .map_err(|_| APIError::InvalidScorerSnapshot(s!("missing header")))?;
+    let (magic, version) = header.split_at(SCORER_SNAPSHOT_MAGIC.len());
+    if magic != SCORER_SNAPSHOT_MAGIC {
+        return Err(APIError::InvalidScorerSnapshot(s!("unknown header")));
+    }
+    if version[0] != SCORER_SNAPSHOT_VERSION {
+        return Err(APIError::InvalidScorerSnapshot(format!(
+            "unsupported version {}",
+            version[0]
+        )));
+    }
+    let mut scorer = vec![];
+    r.read_to_end(&mut scorer)?;
+
+    let mut reader = &scorer[..];
+    let args = (
+        ProbabilisticScoringDecayParameters::default(),
+        graph,
+        logger,
+    );
+    ProbabilisticScorer::read(&mut reader, args)
+        .map_err(|e| APIError::InvalidScorerSnapshot(format!("{e:?}")))?;
+    if !reader.is_empty() {
+        return Err(APIError::InvalidScorerSnapshot(s!("trailing data")));
+    }
+
+    let mut tmp_path = path.to_path_buf();
+    tmp_path.set_extension("stmp");
+    write_file(&tmp_path, &scorer)?;
+    fs::rename(tmp_path, path)?;
+    tracing::info!("imported scorer to {}", path.display());
+    Ok(())
+}
+
 pub(crate) fn read_channel_ids_info(path: &Path) -> ChannelIdsMap {
     if let Ok(file) = File::open(path) {
         if let Ok(info) = ChannelIdsMap::read(&mut BufReader::new(file)) {
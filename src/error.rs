@@ -6,6 +6,7 @@ use axum::{
 };
 use rgb_lib::{BitcoinNetwork, Error as RgbLibError};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub(crate) struct APIErrorResponse {
@@ -47,6 +48,9 @@ pub enum APIError {
     #[error("Cannot call other APIs while node is changing state")]
     ChangingState,
 
+    #[error("Not enough disk space to write {}", .0.display())]
+    DiskFull(PathBuf),
+
     #[error("Another payment for this invoice is already in status {0}")]
     DuplicatePayment(String),
 
@@ -113,6 +117,9 @@ pub enum APIError {
     #[error("Invalid announce alias: {0}")]
     InvalidAnnounceAlias(String),
 
+    #[error("Invalid announce color: {0}")]
+    InvalidAnnounceColor(String),
+
     #[error("Invalid asset ID: {0}")]
     InvalidAssetID(String),
 
@@ -173,6 +180,9 @@ pub enum APIError {
     #[error("Invalid password: {0}")]
     InvalidPassword(String),
 
+    #[error("Invalid path component: {0}")]
+    InvalidPath(String),
+
     #[error("Invalid peer info: {0}")]
     InvalidPeerInfo(String),
 
@@ -200,6 +210,12 @@ pub enum APIError {
     #[error("Invalid request: {0}")]
     InvalidRequest(String),
 
+    #[error("Invalid scorer snapshot: {0}")]
+    InvalidScorerSnapshot(String),
+
+    #[error("Invalid scorer snapshot path")]
+    InvalidScorerSnapshotPath,
+
     #[error("Invalid swap: {0}")]
     InvalidSwap(String),
 
@@ -287,6 +303,9 @@ pub enum APIError {
     #[error("Payment not found: {0}")]
     PaymentNotFound(String),
 
+    #[error("Unable to resolve peer host: {0}")]
+    PeerHostUnresolvable(String),
+
     #[error("Recipient ID already used")]
     RecipientIDAlreadyUsed,
 
@@ -433,6 +452,11 @@ impl From<RgbLibError> for APIError {
 impl IntoResponse for APIError {
     fn into_response(self) -> Response {
         let (status, error, name) = match self {
+            APIError::DiskFull(_) => (
+                StatusCode::INSUFFICIENT_STORAGE,
+                self.to_string(),
+                self.name(),
+            ),
             APIError::FailedClosingChannel(_)
             | APIError::FailedInvoiceCreation(_)
             | APIError::FailedIssuingAsset(_)
@@ -454,6 +478,7 @@ impl IntoResponse for APIError {
             | APIError::InvalidAmount(_)
             | APIError::InvalidAnnounceAddresses(_)
             | APIError::InvalidAnnounceAlias(_)
+            | APIError::InvalidAnnounceColor(_)
             | APIError::InvalidAssetID(_)
             | APIError::InvalidAssignment
             | APIError::InvalidAttachments(_)
@@ -470,6 +495,7 @@ impl IntoResponse for APIError {
             | APIError::InvalidNodeIds(_)
             | APIError::InvalidOnionData(_)
             | APIError::InvalidPassword(_)
+            | APIError::InvalidPath(_)
             | APIError::InvalidPaymentHash(_)
             | APIError::PaymentHashAlreadyUsed
             | APIError::InvalidPaymentPreimage
@@ -481,6 +507,8 @@ impl IntoResponse for APIError {
             | APIError::InvalidRecipientID
             | APIError::InvalidRecipientNetwork
             | APIError::InvalidRequest(_)
+            | APIError::InvalidScorerSnapshot(_)
+            | APIError::InvalidScorerSnapshotPath
             | APIError::InvalidSwap(_)
             | APIError::InvalidSwapString(_, _)
             | APIError::InvalidTicker(_)
@@ -492,6 +520,7 @@ impl IntoResponse for APIError {
             | APIError::MediaFileNotProvided
             | APIError::MissingSwapPaymentPreimage
             | APIError::OutputBelowDustLimit
+            | APIError::PeerHostUnresolvable(_)
             | APIError::ClaimDeadlineExceeded
             | APIError::UnsupportedBackupVersion { .. } => {
                 (StatusCode::BAD_REQUEST, self.to_string(), self.name())
@@ -571,6 +600,9 @@ impl IntoResponse for APIError {
 /// The error variants returned by the app
 #[derive(Debug, thiserror::Error)]
 pub enum AppError {
+    #[error("Data directory {} is already in use by another node", .0.display())]
+    DataDirInUse(PathBuf),
+
     #[error("The provided authentication args are invalid")]
     InvalidAuthenticationArgs,
 
@@ -585,6 +617,9 @@ pub enum AppError {
 
     #[error("Port {0} is unavailable")]
     UnavailablePort(u16),
+
+    #[error("Logs directory {} is not writable: {1}", .0.display())]
+    UnwritableLogsDir(PathBuf, String),
 }
 
 /// The error variants returned by the authentication checks
@@ -1,4 +1,4 @@
-use amplify::{map, s};
+use amplify::map;
 use bitcoin::blockdata::locktime::absolute::LockTime;
 use bitcoin::psbt::{ExtractTxError, Psbt};
 use bitcoin::secp256k1::{All, PublicKey, Secp256k1};
@@ -13,6 +13,7 @@ use lightning::ln::channelmanager::{self, PaymentId, RecentPaymentDetails};
 use lightning::ln::channelmanager::{
     ChainParameters, ChannelManagerReadArgs, SimpleArcChannelManager,
 };
+use lightning::ln::msgs::DecodeError;
 use lightning::ln::msgs::SocketAddress;
 use lightning::ln::peer_handler::{
     IgnoringMessageHandler, MessageHandler, PeerManager as LdkPeerManager,
@@ -43,9 +44,12 @@ use lightning::util::persist::{
     KVStoreSync, MonitorUpdatingPersister, OUTPUT_SWEEPER_PERSISTENCE_KEY,
     OUTPUT_SWEEPER_PERSISTENCE_PRIMARY_NAMESPACE, OUTPUT_SWEEPER_PERSISTENCE_SECONDARY_NAMESPACE,
 };
-use lightning::util::ser::{ReadableArgs, Writeable};
+use lightning::util::ser::{ReadableArgs, RequiredWrapper, Writeable, Writer};
 use lightning::util::sweep as ldk_sweep;
-use lightning::{chain, impl_writeable_tlv_based, impl_writeable_tlv_based_enum};
+use lightning::{
+    chain, impl_writeable_tlv_based, impl_writeable_tlv_based_enum, read_tlv_fields,
+    write_tlv_fields,
+};
 use lightning_background_processor::{process_events_async, GossipSync, NO_LIQUIDITY_MANAGER};
 use lightning_block_sync::gossip::TokioSpawner;
 use lightning_block_sync::init;
@@ -74,7 +78,7 @@ use rgb_lib::{
     AssetSchema, Assignment, BitcoinNetwork, ConsignmentExt, ContractId, FileContent, RgbTransfer,
     RgbTxid, WitnessOrd,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
 use std::fs;
 use std::hash::{DefaultHasher, Hash, Hasher};
@@ -90,21 +94,24 @@ use time::OffsetDateTime;
 use tokio::runtime::Handle;
 use tokio::sync::watch::Sender;
 use tokio::task::JoinHandle;
+use zeroize::Zeroizing;
 
 use crate::bitcoind::BitcoindClient;
 use crate::disk::{
     self, FilesystemLogger, CHANNEL_IDS_FNAME, CHANNEL_PEER_DATA, INBOUND_PAYMENTS_FNAME,
-    MAKER_SWAPS_FNAME, OUTBOUND_PAYMENTS_FNAME, OUTPUT_SPENDER_TXES, TAKER_SWAPS_FNAME,
+    MAKER_SWAPS_FNAME, NETWORK_GRAPH_FNAME, NODE_ALIAS_FNAME, NODE_COLOR_FNAME,
+    OUTBOUND_PAYMENTS_FNAME, OUTPUT_SPENDER_TXES, SCORER_FNAME, TAKER_SWAPS_FNAME,
 };
 use crate::error::APIError;
 use crate::rgb::{check_rgb_proxy_endpoint, get_rgb_channel_info_optional, RgbLibWalletWrapper};
 use crate::routes::{HTLCStatus, SwapStatus, UnlockRequest, DUST_LIMIT_MSAT};
 use crate::swap::SwapData;
 use crate::utils::{
-    check_port_is_available, connect_peer_if_necessary, do_connect_peer, get_current_timestamp,
-    hex_str, AppState, StaticState, UnlockedAppState, ELECTRUM_URL_MAINNET, ELECTRUM_URL_REGTEST,
-    ELECTRUM_URL_SIGNET, ELECTRUM_URL_TESTNET, ELECTRUM_URL_TESTNET4, PROXY_ENDPOINT_LOCAL,
-    PROXY_ENDPOINT_PUBLIC,
+    check_port_is_available, connect_peer_if_necessary, default_indexer_url,
+    default_proxy_endpoint, do_connect_peer, get_current_timestamp, hex_str,
+    resolve_socket_address, validate_and_parse_announce_alias, validate_and_parse_announce_color,
+    value_or_env, warn_indexer_network_mismatch, AppState, StaticState, UnlockedAppState,
+    INDEXER_URL_ENV_KEY, PROXY_ENDPOINT_ENV_KEY,
 };
 
 pub(crate) const FEE_RATE: u64 = 7;
@@ -174,13 +181,49 @@ impl_writeable_tlv_based!(OutboundPaymentInfoStorage, {
     (0, payments, required),
 });
 
+/// Side of the swaps held by a [`SwapMap`], which also determines the file they're persisted to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SwapRole {
+    Maker,
+    Taker,
+}
+
+impl SwapRole {
+    pub(crate) fn fname(&self) -> &'static str {
+        match self {
+            SwapRole::Maker => MAKER_SWAPS_FNAME,
+            SwapRole::Taker => TAKER_SWAPS_FNAME,
+        }
+    }
+}
+
 pub(crate) struct SwapMap {
+    pub(crate) role: SwapRole,
     pub(crate) swaps: LdkHashMap<PaymentHash, SwapData>,
 }
 
-impl_writeable_tlv_based!(SwapMap, {
-    (0, swaps, required),
-});
+// the role is not persisted, it's implied by the file the swaps are read from
+impl Writeable for SwapMap {
+    fn write<W: Writer>(&self, writer: &mut W) -> Result<(), lightning::io::Error> {
+        write_tlv_fields!(writer, {
+            (0, self.swaps, required),
+        });
+        Ok(())
+    }
+}
+
+impl ReadableArgs<SwapRole> for SwapMap {
+    fn read<R: lightning::io::Read>(reader: &mut R, role: SwapRole) -> Result<Self, DecodeError> {
+        let mut swaps = RequiredWrapper(None);
+        read_tlv_fields!(reader, {
+            (0, swaps, required),
+        });
+        Ok(SwapMap {
+            role,
+            swaps: swaps.0.unwrap(),
+        })
+    }
+}
 
 pub(crate) struct ChannelIdsMap {
     pub(crate) channel_ids: LdkHashMap<ChannelId, ChannelId>,
@@ -190,11 +233,42 @@ impl_writeable_tlv_based!(ChannelIdsMap, {
     (0, channel_ids, required),
 });
 
+impl ChannelIdsMap {
+    /// Final channel IDs mapped from more than one temporary channel ID, along with the
+    /// temporary channel IDs pointing to each of them
+    pub(crate) fn collisions(&self) -> Vec<(ChannelId, Vec<ChannelId>)> {
+        let mut by_channel_id: HashMap<ChannelId, Vec<ChannelId>> = HashMap::new();
+        for (temporary_channel_id, channel_id) in self.channel_ids.iter() {
+            by_channel_id
+                .entry(*channel_id)
+                .or_default()
+                .push(*temporary_channel_id);
+        }
+        by_channel_id
+            .into_iter()
+            .filter(|(_, temporary_channel_ids)| temporary_channel_ids.len() > 1)
+            .collect()
+    }
+
+    /// Remove mappings whose final channel ID is not among the given live channel IDs,
+    /// returning the number of removed mappings
+    pub(crate) fn reconcile(&mut self, live_channel_ids: &HashSet<ChannelId>) -> usize {
+        let len_before = self.channel_ids.len();
+        self.channel_ids
+            .retain(|_, channel_id| live_channel_ids.contains(channel_id));
+        len_before - self.channel_ids.len()
+    }
+}
+
 impl UnlockedAppState {
-    pub(crate) fn add_maker_swap(&self, payment_hash: PaymentHash, swap: SwapData) {
+    pub(crate) fn add_maker_swap(
+        &self,
+        payment_hash: PaymentHash,
+        swap: SwapData,
+    ) -> Result<(), APIError> {
         let mut maker_swaps = self.get_maker_swaps();
         maker_swaps.swaps.insert(payment_hash, swap);
-        self.save_maker_swaps(maker_swaps);
+        self.save_swaps(maker_swaps)
     }
 
     fn claimable_should_expire(payment: &PaymentInfo, now_ts: u64, current_height: u32) -> bool {
@@ -215,7 +289,7 @@ impl UnlockedAppState {
         deadline_passed || invoice_expired
     }
 
-    pub(crate) fn expire_claimables(&self) {
+    pub(crate) fn expire_claimables(&self) -> Result<(), APIError> {
         let now = get_current_timestamp();
         let height = self.channel_manager.current_best_block().height;
         let candidates: Vec<PaymentHash> = {
@@ -262,11 +336,16 @@ impl UnlockedAppState {
                 None,
                 claimable.amt_msat,
                 self.channel_manager.get_our_node_id(),
-            );
+            )?;
         }
+        Ok(())
     }
 
-    pub(crate) fn update_maker_swap_status(&self, payment_hash: &PaymentHash, status: SwapStatus) {
+    pub(crate) fn update_maker_swap_status(
+        &self,
+        payment_hash: &PaymentHash,
+        status: SwapStatus,
+    ) -> Result<(), APIError> {
         let mut maker_swaps = self.get_maker_swaps();
         let maker_swap = maker_swaps.swaps.get_mut(payment_hash).unwrap();
         match &status {
@@ -277,17 +356,21 @@ impl UnlockedAppState {
             SwapStatus::Waiting => panic!("this doesn't make sense: swap starts in Waiting status"),
         }
         maker_swap.status = status;
-        self.save_maker_swaps(maker_swaps);
+        self.save_swaps(maker_swaps)
     }
 
     pub(crate) fn is_maker_swap(&self, payment_hash: &PaymentHash) -> bool {
         self.maker_swaps().contains_key(payment_hash)
     }
 
-    pub(crate) fn add_taker_swap(&self, payment_hash: PaymentHash, swap: SwapData) {
+    pub(crate) fn add_taker_swap(
+        &self,
+        payment_hash: PaymentHash,
+        swap: SwapData,
+    ) -> Result<(), APIError> {
         let mut taker_swaps = self.get_taker_swaps();
         taker_swaps.swaps.insert(payment_hash, swap);
-        self.save_taker_swaps(taker_swaps);
+        self.save_swaps(taker_swaps)
     }
 
     pub(crate) fn upsert_claimable_payment(
@@ -297,7 +380,7 @@ impl UnlockedAppState {
         amt_msat: u64,
         claim_deadline_height: Option<u32>,
         payee_pubkey: PublicKey,
-    ) {
+    ) -> Result<(), APIError> {
         let now_ts = get_current_timestamp();
         let mut inbound = self.get_inbound_payments();
         match inbound.payments.entry(payment_hash) {
@@ -327,10 +410,14 @@ impl UnlockedAppState {
                 });
             }
         }
-        self.save_inbound_payments(inbound);
+        self.save_inbound_payments(inbound)
     }
 
-    pub(crate) fn update_taker_swap_status(&self, payment_hash: &PaymentHash, status: SwapStatus) {
+    pub(crate) fn update_taker_swap_status(
+        &self,
+        payment_hash: &PaymentHash,
+        status: SwapStatus,
+    ) -> Result<(), APIError> {
         let mut taker_swaps = self.get_taker_swaps();
         let taker_swap = taker_swaps.swaps.get_mut(payment_hash).unwrap();
         match &status {
@@ -341,23 +428,15 @@ impl UnlockedAppState {
             SwapStatus::Waiting => panic!("this doesn't make sense: swap starts in Waiting status"),
         }
         taker_swap.status = status;
-        self.save_taker_swaps(taker_swaps);
+        self.save_swaps(taker_swaps)
     }
 
     pub(crate) fn is_taker_swap(&self, payment_hash: &PaymentHash) -> bool {
         self.taker_swaps().contains_key(payment_hash)
     }
 
-    fn save_maker_swaps(&self, swaps: MutexGuard<SwapMap>) {
-        self.fs_store
-            .write("", "", MAKER_SWAPS_FNAME, swaps.encode())
-            .unwrap();
-    }
-
-    fn save_taker_swaps(&self, swaps: MutexGuard<SwapMap>) {
-        self.fs_store
-            .write("", "", TAKER_SWAPS_FNAME, swaps.encode())
-            .unwrap();
+    fn save_swaps(&self, swaps: MutexGuard<SwapMap>) -> Result<(), APIError> {
+        disk::write_state(&self.fs_store, swaps.role.fname(), swaps.encode())
     }
 
     pub(crate) fn maker_swaps(&self) -> LdkHashMap<PaymentHash, SwapData> {
@@ -368,10 +447,14 @@ impl UnlockedAppState {
         self.get_taker_swaps().swaps.clone()
     }
 
-    pub(crate) fn add_inbound_payment(&self, payment_hash: PaymentHash, payment_info: PaymentInfo) {
+    pub(crate) fn add_inbound_payment(
+        &self,
+        payment_hash: PaymentHash,
+        payment_info: PaymentInfo,
+    ) -> Result<(), APIError> {
         let mut inbound = self.get_inbound_payments();
         inbound.payments.insert(payment_hash, payment_info);
-        self.save_inbound_payments(inbound);
+        self.save_inbound_payments(inbound)
     }
 
     pub(crate) fn add_outbound_payment(
@@ -388,11 +471,13 @@ impl UnlockedAppState {
             }
         }
         outbound.payments.insert(payment_id, payment_info);
-        self.save_outbound_payments(outbound);
-        Ok(())
+        self.save_outbound_payments(outbound)
     }
 
-    fn fail_outbound_pending_payments(&self, recent_payments_payment_ids: Vec<PaymentId>) {
+    fn fail_outbound_pending_payments(
+        &self,
+        recent_payments_payment_ids: Vec<PaymentId>,
+    ) -> Result<(), APIError> {
         let mut outbound = self.get_outbound_payments();
         let mut failed = false;
         for (payment_id, payment_info) in outbound
@@ -407,11 +492,14 @@ impl UnlockedAppState {
             }
         }
         if failed {
-            self.save_outbound_payments(outbound);
+            self.save_outbound_payments(outbound)?;
         }
+        Ok(())
     }
 
-    pub(crate) fn list_updated_inbound_payments(&self) -> LdkHashMap<PaymentHash, PaymentInfo> {
+    pub(crate) fn list_updated_inbound_payments(
+        &self,
+    ) -> Result<LdkHashMap<PaymentHash, PaymentInfo>, APIError> {
         let now = get_current_timestamp();
         let mut inbound = self.get_inbound_payments();
         let mut failed = false;
@@ -430,9 +518,9 @@ impl UnlockedAppState {
         }
         let payments = inbound.payments.clone();
         if failed {
-            self.save_inbound_payments(inbound);
+            self.save_inbound_payments(inbound)?;
         }
-        payments
+        Ok(payments)
     }
 
     pub(crate) fn inbound_payments(&self) -> LdkHashMap<PaymentHash, PaymentInfo> {
@@ -443,16 +531,18 @@ impl UnlockedAppState {
         self.get_outbound_payments().payments.clone()
     }
 
-    pub(crate) fn save_inbound_payments(&self, inbound: MutexGuard<InboundPaymentInfoStorage>) {
-        self.fs_store
-            .write("", "", INBOUND_PAYMENTS_FNAME, inbound.encode())
-            .unwrap();
+    pub(crate) fn save_inbound_payments(
+        &self,
+        inbound: MutexGuard<InboundPaymentInfoStorage>,
+    ) -> Result<(), APIError> {
+        disk::write_state(&self.fs_store, INBOUND_PAYMENTS_FNAME, inbound.encode())
     }
 
-    fn save_outbound_payments(&self, outbound: MutexGuard<OutboundPaymentInfoStorage>) {
-        self.fs_store
-            .write("", "", OUTBOUND_PAYMENTS_FNAME, outbound.encode())
-            .unwrap();
+    fn save_outbound_payments(
+        &self,
+        outbound: MutexGuard<OutboundPaymentInfoStorage>,
+    ) -> Result<(), APIError> {
+        disk::write_state(&self.fs_store, OUTBOUND_PAYMENTS_FNAME, outbound.encode())
     }
 
     pub fn upsert_inbound_payment(
@@ -463,7 +553,7 @@ impl UnlockedAppState {
         secret: Option<PaymentSecret>,
         amt_msat: Option<u64>,
         payee_pubkey: PublicKey,
-    ) {
+    ) -> Result<(), APIError> {
         let mut inbound = self.get_inbound_payments();
         match inbound.payments.entry(payment_hash) {
             Entry::Occupied(mut e) => {
@@ -497,7 +587,7 @@ impl UnlockedAppState {
                 });
             }
         }
-        self.save_inbound_payments(inbound);
+        self.save_inbound_payments(inbound)
     }
 
     pub(crate) fn update_outbound_payment(
@@ -505,23 +595,27 @@ impl UnlockedAppState {
         payment_id: PaymentId,
         status: HTLCStatus,
         preimage: Option<PaymentPreimage>,
-    ) -> PaymentInfo {
+    ) -> Result<PaymentInfo, APIError> {
         let mut outbound = self.get_outbound_payments();
         let payment_info = outbound.payments.get_mut(&payment_id).unwrap();
         payment_info.status = status;
         payment_info.preimage = preimage;
         payment_info.updated_at = get_current_timestamp();
         let payment = (*payment_info).clone();
-        self.save_outbound_payments(outbound);
-        payment
+        self.save_outbound_payments(outbound)?;
+        Ok(payment)
     }
 
-    pub(crate) fn update_outbound_payment_status(&self, payment_id: PaymentId, status: HTLCStatus) {
+    pub(crate) fn update_outbound_payment_status(
+        &self,
+        payment_id: PaymentId,
+        status: HTLCStatus,
+    ) -> Result<(), APIError> {
         let mut outbound = self.get_outbound_payments();
         let payment_info = outbound.payments.get_mut(&payment_id).unwrap();
         payment_info.status = status;
         payment_info.updated_at = get_current_timestamp();
-        self.save_outbound_payments(outbound);
+        self.save_outbound_payments(outbound)
     }
 
     pub(crate) fn channel_ids(&self) -> LdkHashMap<ChannelId, ChannelId> {
@@ -532,15 +626,28 @@ impl UnlockedAppState {
         &self,
         former_temporary_channel_id: ChannelId,
         channel_id: ChannelId,
-    ) {
+    ) -> Result<(), APIError> {
         let mut channel_ids_map = self.get_channel_ids_map();
+        if let Some((other_temporary_channel_id, _)) =
+            channel_ids_map
+                .channel_ids
+                .iter()
+                .find(|(tmp_chan_id, chan_id)| {
+                    **chan_id == channel_id && **tmp_chan_id != former_temporary_channel_id
+                })
+        {
+            tracing::warn!(
+                "Channel {channel_id} is already mapped from temporary channel \
+                {other_temporary_channel_id}, also mapping it from {former_temporary_channel_id}"
+            );
+        }
         channel_ids_map
             .channel_ids
             .insert(former_temporary_channel_id, channel_id);
-        self.save_channel_ids_map(channel_ids_map);
+        self.save_channel_ids_map(channel_ids_map)
     }
 
-    pub(crate) fn delete_channel_id(&self, channel_id: ChannelId) {
+    pub(crate) fn delete_channel_id(&self, channel_id: ChannelId) -> Result<(), APIError> {
         let mut channel_ids_map = self.get_channel_ids_map();
         if let Some(temporary_channel_id) = channel_ids_map
             .channel_ids
@@ -555,14 +662,13 @@ impl UnlockedAppState {
             })
         {
             channel_ids_map.channel_ids.remove(&temporary_channel_id);
-            self.save_channel_ids_map(channel_ids_map);
+            self.save_channel_ids_map(channel_ids_map)?;
         }
+        Ok(())
     }
 
-    fn save_channel_ids_map(&self, channel_ids: MutexGuard<ChannelIdsMap>) {
-        self.fs_store
-            .write("", "", CHANNEL_IDS_FNAME, channel_ids.encode())
-            .unwrap();
+    fn save_channel_ids_map(&self, channel_ids: MutexGuard<ChannelIdsMap>) -> Result<(), APIError> {
+        disk::write_state(&self.fs_store, CHANNEL_IDS_FNAME, channel_ids.encode())
     }
 }
 
@@ -686,6 +792,15 @@ fn _update_rgb_channel_amount(ldk_data_dir: &Path, payment_hash: &PaymentHash, r
     }
 }
 
+/// Ask for the event to be replayed if the state it updates couldn't be persisted, e.g. because
+/// the disk is full.
+fn replay_if_unpersisted<T>(res: Result<T, APIError>) -> Result<T, ReplayEvent> {
+    res.map_err(|e| {
+        tracing::error!("Failed to persist the state updated by an event: {e}");
+        ReplayEvent()
+    })
+}
+
 async fn handle_ldk_events(
     event: Event,
     unlocked_state: Arc<UnlockedAppState>,
@@ -894,14 +1009,14 @@ async fn handle_ldk_events(
                     unlocked_state
                         .channel_manager
                         .fail_htlc_backwards(&payment_hash);
-                    unlocked_state.upsert_inbound_payment(
+                    replay_if_unpersisted(unlocked_state.upsert_inbound_payment(
                         payment_hash,
                         HTLCStatus::Failed,
                         payment_preimage,
                         payment_secret,
                         Some(amount_msat),
                         unlocked_state.channel_manager.get_our_node_id(),
-                    );
+                    ))?;
                     return Ok(());
                 }
             }
@@ -917,14 +1032,14 @@ async fn handle_ldk_events(
                     unlocked_state
                         .channel_manager
                         .fail_htlc_backwards(&payment_hash);
-                    unlocked_state.upsert_inbound_payment(
+                    replay_if_unpersisted(unlocked_state.upsert_inbound_payment(
                         payment_hash,
                         HTLCStatus::Failed,
                         payment_preimage,
                         payment_secret,
                         Some(amount_msat),
                         unlocked_state.channel_manager.get_our_node_id(),
-                    );
+                    ))?;
                     return Ok(());
                 }
             }
@@ -953,13 +1068,13 @@ async fn handle_ldk_events(
                     unlocked_state.channel_manager.claim_funds(preimage);
                 }
                 InvoiceMode::Hodl => {
-                    unlocked_state.upsert_claimable_payment(
+                    replay_if_unpersisted(unlocked_state.upsert_claimable_payment(
                         payment_hash,
                         payment_secret,
                         amount_msat,
                         claim_deadline,
                         unlocked_state.channel_manager.get_our_node_id(),
-                    );
+                    ))?;
                 }
             }
         }
@@ -1019,16 +1134,18 @@ async fn handle_ldk_events(
 
             _update_rgb_channel_amount(&static_state.ldk_data_dir, &payment_hash, true);
             if is_maker_swap {
-                unlocked_state.update_maker_swap_status(&payment_hash, SwapStatus::Succeeded);
+                replay_if_unpersisted(
+                    unlocked_state.update_maker_swap_status(&payment_hash, SwapStatus::Succeeded),
+                )?;
             } else {
-                unlocked_state.upsert_inbound_payment(
+                replay_if_unpersisted(unlocked_state.upsert_inbound_payment(
                     payment_hash,
                     HTLCStatus::Succeeded,
                     payment_preimage,
                     payment_secret,
                     Some(amount_msat),
                     receiver_node_id.unwrap(),
-                );
+                ))?;
             }
         }
         Event::PaymentSent {
@@ -1046,13 +1163,15 @@ async fn handle_ldk_events(
                     payment_hash,
                     payment_preimage
                 );
-                unlocked_state.update_maker_swap_status(&payment_hash, SwapStatus::Succeeded);
+                replay_if_unpersisted(
+                    unlocked_state.update_maker_swap_status(&payment_hash, SwapStatus::Succeeded),
+                )?;
             } else {
-                let payment = unlocked_state.update_outbound_payment(
+                let payment = replay_if_unpersisted(unlocked_state.update_outbound_payment(
                     payment_id.unwrap(),
                     HTLCStatus::Succeeded,
                     Some(payment_preimage),
-                );
+                ))?;
                 tracing::info!(
                     "EVENT: successfully sent payment of {:?} millisatoshis{} from \
                             payment hash {} with preimage {}",
@@ -1120,9 +1239,14 @@ async fn handle_ldk_events(
                     }
                 );
                 if unlocked_state.is_maker_swap(&hash) {
-                    unlocked_state.update_maker_swap_status(&hash, SwapStatus::Failed);
+                    replay_if_unpersisted(
+                        unlocked_state.update_maker_swap_status(&hash, SwapStatus::Failed),
+                    )?;
                 } else {
-                    unlocked_state.update_outbound_payment_status(payment_id, HTLCStatus::Failed);
+                    replay_if_unpersisted(
+                        unlocked_state
+                            .update_outbound_payment_status(payment_id, HTLCStatus::Failed),
+                    )?;
                 }
             } else {
                 tracing::error!(
@@ -1134,7 +1258,9 @@ async fn handle_ldk_events(
                         PaymentFailureReason::RetriesExhausted
                     }
                 );
-                unlocked_state.update_outbound_payment_status(payment_id, HTLCStatus::Failed);
+                replay_if_unpersisted(
+                    unlocked_state.update_outbound_payment_status(payment_id, HTLCStatus::Failed),
+                )?;
             }
         }
         Event::InvoiceReceived { .. } => {
@@ -1178,7 +1304,9 @@ async fn handle_ldk_events(
             }
 
             if unlocked_state.is_taker_swap(&payment_hash) {
-                unlocked_state.update_taker_swap_status(&payment_hash, SwapStatus::Succeeded);
+                replay_if_unpersisted(
+                    unlocked_state.update_taker_swap_status(&payment_hash, SwapStatus::Succeeded),
+                )?;
             }
 
             let read_only_network_graph = unlocked_state.network_graph.read_only();
@@ -1273,7 +1401,9 @@ async fn handle_ldk_events(
                 hex_str(&counterparty_node_id.serialize()),
             );
 
-            unlocked_state.add_channel_id(former_temporary_channel_id.unwrap(), channel_id);
+            replay_if_unpersisted(
+                unlocked_state.add_channel_id(former_temporary_channel_id.unwrap(), channel_id),
+            )?;
 
             let funding_txid = funding_txo.txid.to_string();
             let psbt_path = static_state
@@ -1362,7 +1492,7 @@ async fn handle_ldk_events(
                 reason
             );
 
-            unlocked_state.delete_channel_id(channel_id);
+            replay_if_unpersisted(unlocked_state.delete_channel_id(channel_id))?;
         }
         Event::DiscardFunding { channel_id, .. } => {
             // A "real" node should probably "lock" the UTXOs spent in funding transactions until
@@ -1374,7 +1504,7 @@ async fn handle_ldk_events(
 
             *unlocked_state.rgb_send_lock.lock().unwrap() = false;
 
-            unlocked_state.delete_channel_id(channel_id);
+            replay_if_unpersisted(unlocked_state.delete_channel_id(channel_id))?;
         }
         Event::HTLCIntercepted {
             is_swap,
@@ -1481,7 +1611,9 @@ async fn handle_ldk_events(
 
             if fail {
                 tracing::error!("ERROR: swap doesn't match the whitelisted info, rejecting it");
-                unlocked_state.update_taker_swap_status(&payment_hash, SwapStatus::Failed);
+                replay_if_unpersisted(
+                    unlocked_state.update_taker_swap_status(&payment_hash, SwapStatus::Failed),
+                )?;
                 unlocked_state
                     .channel_manager
                     .fail_intercepted_htlc(intercept_id)
@@ -1490,7 +1622,9 @@ async fn handle_ldk_events(
             }
 
             tracing::debug!("Swap is whitelisted, forwarding the htlc...");
-            unlocked_state.update_taker_swap_status(&payment_hash, SwapStatus::Pending);
+            replay_if_unpersisted(
+                unlocked_state.update_taker_swap_status(&payment_hash, SwapStatus::Pending),
+            )?;
 
             unlocked_state
                 .channel_manager
@@ -1520,7 +1654,7 @@ async fn handle_ldk_events(
         Event::ConnectionNeeded { node_id, addresses } => {
             tokio::spawn(async move {
                 for address in addresses {
-                    if let Ok(sockaddrs) = address.to_socket_addrs() {
+                    if let Ok(sockaddrs) = resolve_socket_address(&address).await {
                         for addr in sockaddrs {
                             let pm = Arc::clone(&unlocked_state.peer_manager);
                             if connect_peer_if_necessary(node_id, addr, pm).await.is_ok() {
@@ -1754,9 +1888,10 @@ impl OutputSpender for RgbOutputSpender {
         }
 
         txes.insert(descriptors_hash, spending_tx.clone());
-        self.fs_store
-            .write("", "", OUTPUT_SPENDER_TXES, txes.encode())
-            .unwrap();
+        if let Err(e) = disk::write_state(&self.fs_store, OUTPUT_SPENDER_TXES, txes.encode()) {
+            tracing::error!("cannot persist output spender txes: {e}");
+            return Err(());
+        }
 
         Ok(spending_tx)
     }
@@ -1808,7 +1943,19 @@ pub(crate) async fn start_ldk(
     }
 
     // RGB setup
-    let indexer_url = if let Some(indexer_url) = &unlock_request.indexer_url {
+    // values from the unlock request take precedence over the environment
+    let custom_indexer_url = value_or_env(
+        unlock_request.indexer_url.clone(),
+        &static_state.env_prefix,
+        INDEXER_URL_ENV_KEY,
+    );
+    let custom_proxy_endpoint = value_or_env(
+        unlock_request.proxy_endpoint.clone(),
+        &static_state.env_prefix,
+        PROXY_ENDPOINT_ENV_KEY,
+    );
+    let indexer_url = if let Some(indexer_url) = &custom_indexer_url {
+        warn_indexer_network_mismatch(indexer_url, bitcoin_network);
         let indexer_protocol = check_indexer_url(indexer_url, bitcoin_network)?;
         tracing::info!(
             "Connected to an indexer with the {} protocol",
@@ -1817,35 +1964,42 @@ pub(crate) async fn start_ldk(
         indexer_url
     } else {
         tracing::info!("Using the default indexer");
-        match bitcoin_network {
-            BitcoinNetwork::Regtest => ELECTRUM_URL_REGTEST,
-            BitcoinNetwork::Signet => ELECTRUM_URL_SIGNET,
-            BitcoinNetwork::Testnet => ELECTRUM_URL_TESTNET,
-            BitcoinNetwork::Testnet4 => ELECTRUM_URL_TESTNET4,
-            BitcoinNetwork::Mainnet => ELECTRUM_URL_MAINNET,
-        }
+        default_indexer_url(bitcoin_network)
     };
-    let proxy_endpoint = if let Some(proxy_endpoint) = &unlock_request.proxy_endpoint {
+    let proxy_endpoint = if let Some(proxy_endpoint) = &custom_proxy_endpoint {
         check_rgb_proxy_endpoint(proxy_endpoint).await?;
         tracing::info!("Using a custom proxy");
         proxy_endpoint
     } else {
         tracing::info!("Using the default proxy");
-        match bitcoin_network {
-            BitcoinNetwork::Signet
-            | BitcoinNetwork::Testnet
-            | BitcoinNetwork::Testnet4
-            | BitcoinNetwork::Mainnet => PROXY_ENDPOINT_PUBLIC,
-            BitcoinNetwork::Regtest => PROXY_ENDPOINT_LOCAL,
-        }
+        default_proxy_endpoint(bitcoin_network)
     };
     let storage_dir_path = app_state.static_state.storage_dir_path.clone();
-    fs::write(storage_dir_path.join(INDEXER_URL_FNAME), indexer_url).expect("able to write");
-    fs::write(
-        storage_dir_path.join(BITCOIN_NETWORK_FNAME),
+    disk::write_file_if_changed(&storage_dir_path.join(INDEXER_URL_FNAME), indexer_url)?;
+    disk::write_file_if_changed(
+        &storage_dir_path.join(BITCOIN_NETWORK_FNAME),
         bitcoin_network.to_string(),
-    )
-    .expect("able to write");
+    )?;
+
+    // Node announcement alias and color, persisted so they don't need to be provided at every
+    // unlock
+    let announce_alias = match unlock_request.announce_alias {
+        Some(alias) => {
+            validate_and_parse_announce_alias(&alias)?;
+            disk::write_file_if_changed(&ldk_data_dir.join(NODE_ALIAS_FNAME), &alias)?;
+            Some(alias)
+        }
+        None => disk::read_file_if_exists(&ldk_data_dir.join(NODE_ALIAS_FNAME))?,
+    };
+    let announce_color = match unlock_request.announce_color {
+        Some(color) => {
+            validate_and_parse_announce_color(&color)?;
+            let color = color.to_lowercase();
+            disk::write_file_if_changed(&ldk_data_dir.join(NODE_COLOR_FNAME), &color)?;
+            Some(color)
+        }
+        None => disk::read_file_if_exists(&ldk_data_dir.join(NODE_COLOR_FNAME))?,
+    };
 
     // Initialize the FeeEstimator
     // BitcoindClient implements the FeeEstimator trait, so it'll act as our fee estimator.
@@ -1913,14 +2067,14 @@ pub(crate) async fn start_ldk(
         .expect("Failed to fetch best block header and best block");
 
     // Initialize routing ProbabilisticScorer
-    let network_graph_path = ldk_data_dir.join("network_graph");
+    let network_graph_path = ldk_data_dir.join(NETWORK_GRAPH_FNAME);
     let network_graph = Arc::new(disk::read_network(
         &network_graph_path,
         network,
         logger.clone(),
     ));
 
-    let scorer_path = ldk_data_dir.join("scorer");
+    let scorer_path = ldk_data_dir.join(SCORER_FNAME);
     let scorer = Arc::new(RwLock::new(disk::read_scorer(
         &scorer_path,
         Arc::clone(&network_graph),
@@ -2002,7 +2156,7 @@ pub(crate) async fn start_ldk(
     };
 
     // Prepare the RGB wallet
-    let mnemonic_str = mnemonic.to_string();
+    let mnemonic_str = Zeroizing::new(mnemonic.to_string());
     let (_, account_xpub_vanilla, _) =
         get_account_data(bitcoin_network, &mnemonic_str, false).unwrap();
     let (_, account_xpub_colored, master_fingerprint) =
@@ -2030,32 +2184,28 @@ pub(crate) async fn start_ldk(
     .await
     .unwrap();
     let rgb_online = rgb_wallet.go_online(false, indexer_url.to_string())?;
-    fs::write(
-        static_state.storage_dir_path.join(WALLET_FINGERPRINT_FNAME),
+    disk::write_file_if_changed(
+        &static_state.storage_dir_path.join(WALLET_FINGERPRINT_FNAME),
         account_xpub_colored.fingerprint().to_string(),
-    )
-    .expect("able to write");
-    fs::write(
-        static_state
+    )?;
+    disk::write_file_if_changed(
+        &static_state
             .storage_dir_path
             .join(WALLET_ACCOUNT_XPUB_COLORED_FNAME),
         account_xpub_colored.to_string(),
-    )
-    .expect("able to write");
-    fs::write(
-        static_state
+    )?;
+    disk::write_file_if_changed(
+        &static_state
             .storage_dir_path
             .join(WALLET_ACCOUNT_XPUB_VANILLA_FNAME),
         account_xpub_vanilla.to_string(),
-    )
-    .expect("able to write");
-    fs::write(
-        static_state
+    )?;
+    disk::write_file_if_changed(
+        &static_state
             .storage_dir_path
             .join(WALLET_MASTER_FINGERPRINT_FNAME),
         master_fingerprint.to_string(),
-    )
-    .expect("able to write");
+    )?;
 
     let rgb_wallet_wrapper = Arc::new(RgbLibWalletWrapper::new(
         Arc::new(Mutex::new(rgb_wallet)),
@@ -2313,16 +2463,32 @@ pub(crate) async fn start_ldk(
 
     // Read swaps info
     let maker_swaps = Arc::new(Mutex::new(disk::read_swaps_info(
-        &ldk_data_dir.join(MAKER_SWAPS_FNAME),
+        &ldk_data_dir,
+        SwapRole::Maker,
     )));
     let taker_swaps = Arc::new(Mutex::new(disk::read_swaps_info(
-        &ldk_data_dir.join(TAKER_SWAPS_FNAME),
+        &ldk_data_dir,
+        SwapRole::Taker,
     )));
 
     // Read channel IDs info
-    let channel_ids_map = Arc::new(Mutex::new(disk::read_channel_ids_info(
-        &ldk_data_dir.join(CHANNEL_IDS_FNAME),
-    )));
+    let mut channel_ids_map = disk::read_channel_ids_info(&ldk_data_dir.join(CHANNEL_IDS_FNAME));
+    let live_channel_ids: HashSet<ChannelId> = channel_manager
+        .list_channels()
+        .iter()
+        .map(|c| c.channel_id)
+        .collect();
+    let pruned = channel_ids_map.reconcile(&live_channel_ids);
+    if pruned > 0 {
+        tracing::info!("Pruned {pruned} channel ID mappings for channels that no longer exist");
+        disk::write_state(&fs_store, CHANNEL_IDS_FNAME, channel_ids_map.encode())?;
+    }
+    for (channel_id, temporary_channel_ids) in channel_ids_map.collisions() {
+        tracing::warn!(
+            "Channel {channel_id} is mapped from multiple temporary channels: {temporary_channel_ids:?}"
+        );
+    }
+    let channel_ids_map = Arc::new(Mutex::new(channel_ids_map));
 
     let unlocked_state = Arc::new(UnlockedAppState {
         channel_manager: Arc::clone(&channel_manager),
@@ -2343,6 +2509,8 @@ pub(crate) async fn start_ldk(
         rgb_send_lock: Arc::new(Mutex::new(false)),
         channel_ids_map,
         proxy_endpoint: proxy_endpoint.to_string(),
+        announce_alias,
+        announce_color,
     });
 
     let recent_payments_payment_ids = channel_manager
@@ -2355,7 +2523,7 @@ pub(crate) async fn start_ldk(
             RecentPaymentDetails::AwaitingInvoice { payment_id } => payment_id,
         })
         .collect::<Vec<PaymentId>>();
-    unlocked_state.fail_outbound_pending_payments(recent_payments_payment_ids);
+    unlocked_state.fail_outbound_pending_payments(recent_payments_payment_ids)?;
 
     // Handle LDK Events
     let unlocked_state_copy = Arc::clone(&unlocked_state);
@@ -2409,7 +2577,7 @@ pub(crate) async fn start_ldk(
         interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
         loop {
             interval.tick().await;
-            match disk::read_channel_peer_data(&peer_data_path) {
+            match disk::read_channel_peer_data(&peer_data_path).await {
                 Ok(info) => {
                     for node_id in connect_cm
                         .list_channels()
@@ -2421,10 +2589,21 @@ pub(crate) async fn start_ldk(
                             return;
                         }
                         for (pubkey, peer_addr) in info.iter() {
-                            if *pubkey == node_id {
-                                let _ =
-                                    do_connect_peer(*pubkey, *peer_addr, Arc::clone(&connect_pm))
-                                        .await;
+                            if *pubkey != node_id {
+                                continue;
+                            }
+                            match resolve_socket_address(peer_addr).await {
+                                Ok(socket_addrs) => {
+                                    for socket_addr in socket_addrs {
+                                        let pm = Arc::clone(&connect_pm);
+                                        if do_connect_peer(*pubkey, socket_addr, pm).await.is_ok() {
+                                            break;
+                                        }
+                                    }
+                                }
+                                Err(e) => tracing::debug!(
+                                    "cannot reconnect to peer {pubkey} at {peer_addr}: {e}"
+                                ),
                             }
                         }
                     }
@@ -2452,19 +2631,14 @@ pub(crate) async fn start_ldk(
             }
         }
     }
-    let ldk_announced_node_name = match unlock_request.announce_alias {
-        Some(s) => {
-            if s.len() > 32 {
-                return Err(APIError::InvalidAnnounceAlias(s!(
-                    "cannot be longer than 32 bytes"
-                )));
-            }
-            let mut bytes = [0; 32];
-            bytes[..s.len()].copy_from_slice(s.as_bytes());
-            bytes
-        }
+    let ldk_announced_node_name = match &announce_alias {
+        Some(alias) => validate_and_parse_announce_alias(alias)?,
         None => [0; 32],
     };
+    let ldk_announced_node_color = match &announce_color {
+        Some(color) => validate_and_parse_announce_color(color)?,
+        None => [0; 3],
+    };
     let peer_man = Arc::clone(&peer_manager);
     let chan_man = Arc::clone(&channel_manager);
     tokio::spawn(async move {
@@ -2484,7 +2658,7 @@ pub(crate) async fn start_ldk(
                 .any(|chan| chan.is_announced)
             {
                 peer_man.broadcast_node_announcement(
-                    [0; 3],
+                    ldk_announced_node_color,
                     ldk_announced_node_name,
                     ldk_announced_listen_addr.clone(),
                 );
@@ -39,32 +39,40 @@ use tracing_subscriber::{
 
 use crate::args::UserArgs;
 use crate::auth::conditional_auth_middleware;
+use crate::disk::daily_file_appender;
 use crate::error::AppError;
 use crate::ldk::stop_ldk;
 use crate::routes::{
     address, asset_balance, asset_metadata, backup, btc_balance, cancel_hodl_invoice,
     change_password, check_indexer_url, check_proxy_endpoint, claim_hodl_invoice, close_channel,
     connect_peer, create_utxos, decode_ln_invoice, decode_rgb_invoice, disconnect_peer,
-    estimate_fee, fail_transfers, get_asset_media, get_channel_id, get_payment, get_swap, init,
-    invoice_status, issue_asset_cfa, issue_asset_nia, issue_asset_uda, keysend, list_assets,
-    list_channels, list_payments, list_peers, list_swaps, list_transactions, list_transfers,
-    list_unspents, ln_invoice, lock, maker_execute, maker_init, network_info, node_info,
-    open_channel, post_asset_media, refresh_transfers, restore, revoke_token, rgb_invoice,
-    send_btc, send_onion_message, send_payment, send_rgb, shutdown, sign_message, sync, taker,
-    unlock,
+    estimate_fee, export_logs, export_payments, export_scorer, fail_transfers, get_asset_media,
+    get_channel_id, get_payment, get_swap, import_scorer, init, invoice_status, issue_asset_cfa,
+    issue_asset_nia, issue_asset_uda, keysend, list_assets, list_channels, list_payments,
+    list_peers, list_swaps, list_transactions, list_transfers, list_unspents, ln_invoice, lock,
+    maker_execute, maker_init, network_info, node_info, open_channel, post_asset_media,
+    refresh_transfers, restore, revoke_token, rgb_invoice, send_btc, send_onion_message,
+    send_payment, send_rgb, shutdown, sign_message, sync, taker, unlock,
 };
-use crate::utils::{start_daemon, AppState, LOGS_DIR};
+use crate::utils::{start_daemon, AppState, LockGuard, LOGS_DIR};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = args::parse_startup_args()?;
 
+    // make sure no other node process is using the same storage directory, before creating or
+    // opening any file inside it
+    let storage_dir_lock = LockGuard::acquire(&args.storage_dir_path)?;
+
     // stdout logger
     let stdout_log = tracing_subscriber::fmt::layer().fmt_fields(TypedFields::default());
 
     // file logger
-    let log_dir = args.storage_dir_path.join(LOGS_DIR);
-    let file_appender = tracing_appender::rolling::daily(&log_dir, "rln.log");
+    let log_dir = args
+        .logs_dir
+        .clone()
+        .unwrap_or_else(|| args.storage_dir_path.join(LOGS_DIR));
+    let file_appender = daily_file_appender(&log_dir)?;
     let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
     let file_log = tracing_subscriber::fmt::layer()
         .with_file(true)
@@ -81,7 +89,7 @@ async fn main() -> Result<()> {
 
     let addr = SocketAddr::from(([0, 0, 0, 0], args.daemon_listening_port));
 
-    let (router, app_state) = app(args).await?;
+    let (router, app_state) = app(args, storage_dir_lock).await?;
 
     tracing::info!("Listening on {}", addr);
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
@@ -93,8 +101,11 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-pub(crate) async fn app(args: UserArgs) -> Result<(Router, Arc<AppState>), AppError> {
-    let app_state = start_daemon(&args).await?;
+pub(crate) async fn app(
+    args: UserArgs,
+    storage_dir_lock: LockGuard,
+) -> Result<(Router, Arc<AppState>), AppError> {
+    let app_state = start_daemon(&args, storage_dir_lock).await?;
 
     let router = Router::new()
         .route(
@@ -122,11 +133,15 @@ pub(crate) async fn app(args: UserArgs) -> Result<(Router, Arc<AppState>), AppEr
         .route("/decodergbinvoice", post(decode_rgb_invoice))
         .route("/disconnectpeer", post(disconnect_peer))
         .route("/estimatefee", post(estimate_fee))
+        .route("/exportlogs", post(export_logs))
+        .route("/exportpayments", post(export_payments))
+        .route("/exportscorer", post(export_scorer))
         .route("/failtransfers", post(fail_transfers))
         .route("/getassetmedia", post(get_asset_media))
         .route("/getchannelid", post(get_channel_id))
         .route("/getpayment", post(get_payment))
         .route("/getswap", post(get_swap))
+        .route("/importscorer", post(import_scorer))
         .route("/init", post(init))
         .route("/invoicestatus", post(invoice_status))
         .route("/issueassetcfa", post(issue_asset_cfa))
@@ -241,6 +256,9 @@ async fn shutdown_signal(app_state: Arc<AppState>) {
         tokio::time::sleep(Duration::from_millis(300)).await;
     }
     stop_ldk(app_state.clone()).await;
+
+    // release the storage directory so another node process can use it
+    app_state.storage_dir_lock.lock().unwrap().take();
 }
 
 // workaround for https://github.com/tokio-rs/tracing/issues/1372
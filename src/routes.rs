@@ -1,6 +1,9 @@
 use amplify::{map, s, Display};
 use axum::{
+    body::Body,
     extract::{Multipart, State},
+    http::header,
+    response::IntoResponse,
     Json,
 };
 use axum_extra::extract::WithRejection;
@@ -9,14 +12,13 @@ use bitcoin::hashes::sha256::{self, Hash as Sha256};
 use bitcoin::hashes::Hash;
 use bitcoin::secp256k1::PublicKey;
 use bitcoin::{Network, ScriptBuf};
+use chrono::DateTime;
 use hex::DisplayHex;
+use lightning::ln::msgs::SocketAddress;
 use lightning::ln::{channelmanager::OptionalOfferPaymentParams, types::ChannelId};
 use lightning::offers::offer::{self, Offer};
 use lightning::onion_message::messenger::Destination;
-use lightning::rgb_utils::{
-    get_rgb_channel_info_path, get_rgb_payment_info_path, parse_rgb_channel_info,
-    parse_rgb_payment_info, STATIC_BLINDING,
-};
+use lightning::rgb_utils::{get_rgb_channel_info_path, parse_rgb_channel_info, STATIC_BLINDING};
 use lightning::routing::gossip::RoutingFees;
 use lightning::routing::router::{Path as LnPath, Route, RouteHint, RouteHintHop};
 use lightning::sign::EntropySource;
@@ -63,7 +65,7 @@ use rgb_lib::{
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
-    net::ToSocketAddrs,
+    convert::Infallible,
     path::{Path, PathBuf},
     str::FromStr,
     sync::Arc,
@@ -74,25 +76,28 @@ use tokio::{
     io::{AsyncReadExt, AsyncWriteExt, BufReader},
     sync::MutexGuard as TokioMutexGuard,
 };
+use zeroize::Zeroizing;
 
 use crate::ldk::{start_ldk, stop_ldk, LdkBackgroundServices, MIN_CHANNEL_CONFIRMATIONS};
 use crate::swap::{SwapData, SwapInfo, SwapString};
 use crate::utils::{
     check_already_initialized, check_channel_id, check_password_strength, check_password_validity,
     encrypt_and_save_mnemonic, get_max_local_rgb_amount, get_mnemonic_path, get_route, hex_str,
-    hex_str_to_compressed_pubkey, hex_str_to_vec, validate_and_parse_payment_hash,
-    validate_and_parse_payment_preimage, UnlockedAppState, UserOnionMessageContents,
+    hex_str_to_compressed_pubkey, hex_str_to_vec, safe_join, validate_and_parse_payment_hash,
+    validate_and_parse_payment_preimage, verify_password, UnlockedAppState,
+    UserOnionMessageContents,
 };
 use crate::{
     backup::{do_backup, restore_backup},
     rgb::{check_rgb_proxy_endpoint, get_rgb_channel_info_optional},
 };
 use crate::{
-    disk::{self, CHANNEL_PEER_DATA},
+    disk::{self, read_logs_between, CHANNEL_PEER_DATA, NETWORK_GRAPH_FNAME, SCORER_FNAME},
     error::APIError,
     ldk::{InvoiceMode, PaymentInfo, FEE_RATE, UTXO_SIZE_SAT},
     utils::{
-        connect_peer_if_necessary, get_current_timestamp, no_cancel, parse_peer_info, AppState,
+        connect_peer_if_necessary, get_current_timestamp, inbound_payment, no_cancel,
+        outbound_payment, parse_peer_info, payments_csv_rows, resolve_socket_address, AppState,
     },
 };
 
@@ -533,6 +538,27 @@ pub(crate) struct EstimateFeeResponse {
     pub(crate) fee_rate: f64,
 }
 
+#[derive(Deserialize, Serialize)]
+pub(crate) struct ExportLogsRequest {
+    pub(crate) start_timestamp: u64,
+    pub(crate) end_timestamp: u64,
+}
+
+#[derive(Deserialize, Serialize)]
+pub(crate) struct ExportLogsResponse {
+    pub(crate) lines: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub(crate) struct ExportPaymentsRequest {
+    pub(crate) direction: Option<PaymentDirection>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub(crate) struct ExportScorerRequest {
+    pub(crate) snapshot_path: String,
+}
+
 #[derive(Deserialize, Serialize)]
 pub(crate) struct FailTransfersRequest {
     pub(crate) batch_transfer_idx: Option<i32>,
@@ -619,6 +645,11 @@ impl From<RgbLibIndexerProtocol> for IndexerProtocol {
     }
 }
 
+#[derive(Deserialize, Serialize)]
+pub(crate) struct ImportScorerRequest {
+    pub(crate) snapshot_path: String,
+}
+
 #[derive(Deserialize, Serialize)]
 pub(crate) struct InitRequest {
     pub(crate) password: String,
@@ -853,6 +884,11 @@ pub(crate) struct NodeInfoResponse {
     pub(crate) channel_asset_max_amount: u64,
     pub(crate) network_nodes: usize,
     pub(crate) network_channels: usize,
+    pub(crate) announce_alias: Option<String>,
+    pub(crate) announce_color: Option<String>,
+    pub(crate) ldk_log_lines_written: u64,
+    pub(crate) ldk_log_bytes_written: u64,
+    pub(crate) ldk_log_dropped_lines: u64,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -874,6 +910,12 @@ pub(crate) struct OpenChannelResponse {
     pub(crate) temporary_channel_id: String,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub(crate) enum PaymentDirection {
+    Inbound,
+    Outbound,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub(crate) struct Payment {
     pub(crate) amt_msat: Option<u64>,
@@ -1220,6 +1262,7 @@ pub(crate) struct UnlockRequest {
     pub(crate) proxy_endpoint: Option<String>,
     pub(crate) announce_addresses: Vec<String>,
     pub(crate) announce_alias: Option<String>,
+    pub(crate) announce_color: Option<String>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -1383,8 +1426,9 @@ pub(crate) async fn backup(
     no_cancel(async move {
         let _guard = state.check_locked().await?;
 
-        let _mnemonic =
-            check_password_validity(&payload.password, &state.static_state.storage_dir_path)?;
+        if !verify_password(&payload.password, &state.static_state.storage_dir_path)? {
+            return Err(APIError::WrongPassword);
+        }
 
         do_backup(
             &state.static_state.storage_dir_path,
@@ -1459,7 +1503,7 @@ pub(crate) async fn cancel_hodl_invoice(
             None,
             payment_info.amt_msat,
             unlocked_state.channel_manager.get_our_node_id(),
-        );
+        )?;
 
         Ok(Json(EmptyResponse {}))
     })
@@ -1480,7 +1524,7 @@ pub(crate) async fn change_password(
 
         encrypt_and_save_mnemonic(
             payload.new_password,
-            mnemonic.to_string(),
+            &Zeroizing::new(mnemonic.to_string()),
             &get_mnemonic_path(&state.static_state.storage_dir_path),
         )?;
 
@@ -1573,7 +1617,7 @@ pub(crate) async fn claim_hodl_invoice(
 
             existing_payment_mut.claiming_since = Some(now_ts);
             existing_payment_mut.updated_at = now_ts;
-            unlocked_state.save_inbound_payments(inbound);
+            unlocked_state.save_inbound_payments(inbound)?;
         }
 
         unlocked_state.channel_manager.claim_funds(preimage);
@@ -1668,7 +1712,8 @@ pub(crate) async fn connect_peer(
         let guard = state.check_unlocked().await?;
         let unlocked_state = guard.as_ref().unwrap();
 
-        let (peer_pubkey, peer_addr) = parse_peer_info(payload.peer_pubkey_and_addr.to_string())?;
+        let (peer_pubkey, peer_addr) =
+            parse_peer_info(payload.peer_pubkey_and_addr.to_string()).await?;
 
         if let Some(peer_addr) = peer_addr {
             connect_peer_if_necessary(peer_pubkey, peer_addr, unlocked_state.peer_manager.clone())
@@ -1676,8 +1721,9 @@ pub(crate) async fn connect_peer(
             disk::persist_channel_peer(
                 &state.static_state.ldk_data_dir.join(CHANNEL_PEER_DATA),
                 &peer_pubkey,
-                &peer_addr,
-            )?;
+                &SocketAddress::from(peer_addr),
+            )
+            .await?;
         } else {
             return Err(APIError::InvalidPeerInfo(s!(
                 "incorrectly formatted peer info. Should be formatted as: `pubkey@host:port`"
@@ -1781,7 +1827,8 @@ pub(crate) async fn disconnect_peer(
         disk::delete_channel_peer(
             &state.static_state.ldk_data_dir.join(CHANNEL_PEER_DATA),
             payload.peer_pubkey,
-        )?;
+        )
+        .await?;
 
         //check the pubkey matches a valid connected peer
         if unlocked_state
@@ -1817,6 +1864,93 @@ pub(crate) async fn estimate_fee(
     Ok(Json(EstimateFeeResponse { fee_rate }))
 }
 
+pub(crate) async fn export_logs(
+    State(state): State<Arc<AppState>>,
+    WithRejection(Json(payload), _): WithRejection<Json<ExportLogsRequest>, APIError>,
+) -> Result<Json<ExportLogsResponse>, APIError> {
+    let datetime = |timestamp: u64| {
+        i64::try_from(timestamp)
+            .ok()
+            .and_then(|t| DateTime::from_timestamp(t, 0))
+            .ok_or_else(|| APIError::InvalidRequest(format!("invalid timestamp {timestamp}")))
+    };
+    let start = datetime(payload.start_timestamp)?;
+    let end = datetime(payload.end_timestamp)?;
+    if start > end {
+        return Err(APIError::InvalidRequest(s!(
+            "start_timestamp is after end_timestamp"
+        )));
+    }
+
+    let logs_dir = state.static_state.logger.logs_dir().to_path_buf();
+    let lines = tokio::task::spawn_blocking(move || read_logs_between(&logs_dir, start, end))
+        .await
+        .unwrap()?;
+
+    Ok(Json(ExportLogsResponse { lines }))
+}
+
+pub(crate) async fn export_payments(
+    State(state): State<Arc<AppState>>,
+    WithRejection(Json(payload), _): WithRejection<Json<ExportPaymentsRequest>, APIError>,
+) -> Result<impl IntoResponse, APIError> {
+    let guard = state.check_unlocked().await?;
+    let unlocked_state = guard.as_ref().unwrap();
+    unlocked_state.expire_claimables()?;
+
+    // rows are built and sent one at a time, reading the RGB info of each payment as it goes
+    let inbound_ldk_data_dir = state.static_state.ldk_data_dir.clone();
+    let outbound_ldk_data_dir = inbound_ldk_data_dir.clone();
+    let include_inbound = payload.direction != Some(PaymentDirection::Outbound);
+    let include_outbound = payload.direction != Some(PaymentDirection::Inbound);
+    let inbound_payments = unlocked_state.list_updated_inbound_payments()?;
+    let outbound_payments = unlocked_state.outbound_payments();
+    let payments = inbound_payments
+        .into_iter()
+        .filter(move |_| include_inbound)
+        .map(move |(payment_hash, info)| {
+            inbound_payment(&payment_hash, &info, &inbound_ldk_data_dir)
+        })
+        .chain(
+            outbound_payments
+                .into_iter()
+                .filter(move |_| include_outbound)
+                .map(move |(payment_id, info)| {
+                    outbound_payment(&payment_id, &info, &outbound_ldk_data_dir)
+                }),
+        );
+    let rows = payments_csv_rows(payments).map(Ok::<_, Infallible>);
+
+    Ok((
+        [(header::CONTENT_TYPE, "text/csv")],
+        Body::from_stream(futures::stream::iter(rows)),
+    ))
+}
+
+pub(crate) async fn export_scorer(
+    State(state): State<Arc<AppState>>,
+    WithRejection(Json(payload), _): WithRejection<Json<ExportScorerRequest>, APIError>,
+) -> Result<Json<EmptyResponse>, APIError> {
+    no_cancel(async move {
+        let _guard = state.check_locked().await?;
+
+        let snapshot_path = Path::new(&payload.snapshot_path);
+        if snapshot_path.exists() {
+            return Err(APIError::InvalidScorerSnapshotPath);
+        }
+
+        let mut snapshot = vec![];
+        disk::export_scorer(
+            &state.static_state.ldk_data_dir.join(SCORER_FNAME),
+            &mut snapshot,
+        )?;
+        disk::write_file(snapshot_path, snapshot)?;
+
+        Ok(Json(EmptyResponse {}))
+    })
+    .await
+}
+
 pub(crate) async fn fail_transfers(
     State(state): State<Arc<AppState>>,
     WithRejection(Json(payload), _): WithRejection<Json<FailTransfersRequest>, APIError>,
@@ -1845,13 +1979,13 @@ pub(crate) async fn get_asset_media(
     State(state): State<Arc<AppState>>,
     WithRejection(Json(payload), _): WithRejection<Json<GetAssetMediaRequest>, APIError>,
 ) -> Result<Json<GetAssetMediaResponse>, APIError> {
-    let file_path = state
+    let media_dir = state
         .check_unlocked()
         .await?
         .clone()
         .unwrap()
-        .rgb_get_media_dir()
-        .join(payload.digest.to_lowercase());
+        .rgb_get_media_dir();
+    let file_path = safe_join(&media_dir, &payload.digest.to_lowercase())?;
     if !file_path.exists() {
         return Err(APIError::InvalidMediaDigest);
     }
@@ -1885,74 +2019,27 @@ pub(crate) async fn get_payment(
 ) -> Result<Json<GetPaymentResponse>, APIError> {
     let guard = state.check_unlocked().await?;
     let unlocked_state = guard.as_ref().unwrap();
-    unlocked_state.expire_claimables();
+    unlocked_state.expire_claimables()?;
 
     let requested_ph = validate_and_parse_payment_hash(&payload.payment_hash)?;
 
-    let inbound_payments = unlocked_state.list_updated_inbound_payments();
+    let inbound_payments = unlocked_state.list_updated_inbound_payments()?;
     let outbound_payments = unlocked_state.outbound_payments();
 
+    let ldk_data_dir = &state.static_state.ldk_data_dir;
+
     for (payment_hash, payment_info) in &inbound_payments {
         if payment_hash == &requested_ph {
-            let rgb_payment_info_path_inbound =
-                get_rgb_payment_info_path(payment_hash, &state.static_state.ldk_data_dir, true);
-
-            let (asset_amount, asset_id) = if rgb_payment_info_path_inbound.exists() {
-                let info = parse_rgb_payment_info(&rgb_payment_info_path_inbound);
-                (Some(info.amount), Some(info.contract_id.to_string()))
-            } else {
-                (None, None)
-            };
-
             return Ok(Json(GetPaymentResponse {
-                payment: Payment {
-                    amt_msat: payment_info.amt_msat,
-                    asset_amount,
-                    asset_id,
-                    payment_hash: hex_str(&payment_hash.0),
-                    inbound: true,
-                    status: payment_info.status,
-                    created_at: payment_info.created_at,
-                    updated_at: payment_info.updated_at,
-                    payee_pubkey: payment_info.payee_pubkey.to_string(),
-                    preimage: None,
-                },
+                payment: inbound_payment(payment_hash, payment_info, ldk_data_dir),
             }));
         }
     }
 
     for (payment_id, payment_info) in &outbound_payments {
-        let payment_hash = &PaymentHash(payment_id.0);
-        if payment_hash == &requested_ph {
-            let rgb_payment_info_path_outbound =
-                get_rgb_payment_info_path(payment_hash, &state.static_state.ldk_data_dir, false);
-
-            let (asset_amount, asset_id) = if rgb_payment_info_path_outbound.exists() {
-                let info = parse_rgb_payment_info(&rgb_payment_info_path_outbound);
-                (Some(info.amount), Some(info.contract_id.to_string()))
-            } else {
-                (None, None)
-            };
-
-            let preimage = if matches!(payment_info.status, HTLCStatus::Succeeded) {
-                payment_info.preimage.map(|p| hex_str(&p.0))
-            } else {
-                None
-            };
-
+        if PaymentHash(payment_id.0) == requested_ph {
             return Ok(Json(GetPaymentResponse {
-                payment: Payment {
-                    amt_msat: payment_info.amt_msat,
-                    asset_amount,
-                    asset_id,
-                    payment_hash: hex_str(&payment_hash.0),
-                    inbound: false,
-                    status: payment_info.status,
-                    created_at: payment_info.created_at,
-                    updated_at: payment_info.updated_at,
-                    payee_pubkey: payment_info.payee_pubkey.to_string(),
-                    preimage,
-                },
+                payment: outbound_payment(payment_id, payment_info, ldk_data_dir),
             }));
         }
     }
@@ -1980,12 +2067,12 @@ pub(crate) async fn get_swap(
         }
         if status != swap_data.status {
             if taker {
-                unlocked_state.update_taker_swap_status(payment_hash, status.clone());
+                unlocked_state.update_taker_swap_status(payment_hash, status.clone())?;
             } else {
-                unlocked_state.update_maker_swap_status(payment_hash, status.clone());
+                unlocked_state.update_maker_swap_status(payment_hash, status.clone())?;
             }
         }
-        Swap {
+        Ok::<_, APIError>(Swap {
             payment_hash: payment_hash.to_string(),
             qty_from: swap_data.swap_info.qty_from,
             qty_to: swap_data.swap_info.qty_to,
@@ -1996,21 +2083,21 @@ pub(crate) async fn get_swap(
             initiated_at: swap_data.initiated_at,
             expires_at: swap_data.swap_info.expiry,
             completed_at: swap_data.completed_at,
-        }
+        })
     };
 
     if payload.taker {
         let taker_swaps = unlocked_state.taker_swaps();
         if let Some(sd) = taker_swaps.get(&requested_ph) {
             return Ok(Json(GetSwapResponse {
-                swap: map_swap(&requested_ph, sd, true),
+                swap: map_swap(&requested_ph, sd, true)?,
             }));
         }
     } else {
         let maker_swaps = unlocked_state.maker_swaps();
         if let Some(sd) = maker_swaps.get(&requested_ph) {
             return Ok(Json(GetSwapResponse {
-                swap: map_swap(&requested_ph, sd, false),
+                swap: map_swap(&requested_ph, sd, false)?,
             }));
         }
     }
@@ -2018,6 +2105,34 @@ pub(crate) async fn get_swap(
     Err(APIError::SwapNotFound(payload.payment_hash))
 }
 
+pub(crate) async fn import_scorer(
+    State(state): State<Arc<AppState>>,
+    WithRejection(Json(payload), _): WithRejection<Json<ImportScorerRequest>, APIError>,
+) -> Result<Json<EmptyResponse>, APIError> {
+    no_cancel(async move {
+        let _guard = state.check_locked().await?;
+
+        let snapshot = std::fs::read(&payload.snapshot_path)
+            .map_err(|_| APIError::InvalidScorerSnapshotPath)?;
+
+        let static_state = &state.static_state;
+        let network_graph = Arc::new(disk::read_network(
+            &static_state.ldk_data_dir.join(NETWORK_GRAPH_FNAME),
+            static_state.network.into(),
+            static_state.logger.clone(),
+        ));
+        disk::import_scorer(
+            &static_state.ldk_data_dir.join(SCORER_FNAME),
+            &mut &snapshot[..],
+            network_graph,
+            static_state.logger.clone(),
+        )?;
+
+        Ok(Json(EmptyResponse {}))
+    })
+    .await
+}
+
 pub(crate) async fn init(
     State(state): State<Arc<AppState>>,
     WithRejection(Json(payload), _): WithRejection<Json<InitRequest>, APIError>,
@@ -2037,7 +2152,7 @@ pub(crate) async fn init(
             None => generate_keys(state.static_state.network).mnemonic,
         };
 
-        encrypt_and_save_mnemonic(payload.password, mnemonic.clone(), &mnemonic_path)?;
+        encrypt_and_save_mnemonic(payload.password, &mnemonic, &mnemonic_path)?;
 
         Ok(Json(InitResponse { mnemonic }))
     })
@@ -2084,13 +2199,13 @@ pub(crate) async fn issue_asset_cfa(
             return Err(APIError::OpenChannelInProgress);
         }
 
-        let file_path = payload.file_digest.map(|d: String| {
-            unlocked_state
-                .rgb_get_media_dir()
-                .join(d.to_lowercase())
-                .to_string_lossy()
-                .to_string()
-        });
+        let file_path = payload
+            .file_digest
+            .map(|d: String| {
+                safe_join(&unlocked_state.rgb_get_media_dir(), &d.to_lowercase())
+                    .map(|p| p.to_string_lossy().to_string())
+            })
+            .transpose()?;
 
         let asset = unlocked_state.rgb_issue_asset_cfa(
             payload.name,
@@ -2147,17 +2262,14 @@ pub(crate) async fn issue_asset_uda(
 
         let rgb_media_dir = unlocked_state.rgb_get_media_dir();
         let get_string_path = |d: String| {
-            rgb_media_dir
-                .join(d.to_lowercase())
-                .to_string_lossy()
-                .to_string()
+            safe_join(&rgb_media_dir, &d.to_lowercase()).map(|p| p.to_string_lossy().to_string())
         };
-        let media_file_path = payload.media_file_digest.map(get_string_path);
+        let media_file_path = payload.media_file_digest.map(get_string_path).transpose()?;
         let attachments_file_paths = payload
             .attachments_file_digests
             .into_iter()
             .map(get_string_path)
-            .collect();
+            .collect::<Result<Vec<_>, _>>()?;
 
         let asset = unlocked_state.rgb_issue_asset_uda(
             payload.ticker,
@@ -2263,7 +2375,7 @@ pub(crate) async fn keysend(
             }
             Err(e) => {
                 tracing::error!("ERROR: failed to send payment: {:?}", e);
-                unlocked_state.update_outbound_payment_status(payment_id, HTLCStatus::Failed);
+                unlocked_state.update_outbound_payment_status(payment_id, HTLCStatus::Failed)?;
                 HTLCStatus::Failed
             }
         };
@@ -2440,69 +2552,20 @@ pub(crate) async fn list_payments(
 ) -> Result<Json<ListPaymentsResponse>, APIError> {
     let guard = state.check_unlocked().await?;
     let unlocked_state = guard.as_ref().unwrap();
-    unlocked_state.expire_claimables();
+    unlocked_state.expire_claimables()?;
 
-    let inbound_payments = unlocked_state.list_updated_inbound_payments();
+    let ldk_data_dir = &state.static_state.ldk_data_dir;
+    let inbound_payments = unlocked_state.list_updated_inbound_payments()?;
     let outbound_payments = unlocked_state.outbound_payments();
-    let mut payments = vec![];
-
-    for (payment_hash, payment_info) in &inbound_payments {
-        let rgb_payment_info_path_inbound =
-            get_rgb_payment_info_path(payment_hash, &state.static_state.ldk_data_dir, true);
-
-        let (asset_amount, asset_id) = if rgb_payment_info_path_inbound.exists() {
-            let info = parse_rgb_payment_info(&rgb_payment_info_path_inbound);
-            (Some(info.amount), Some(info.contract_id.to_string()))
-        } else {
-            (None, None)
-        };
-
-        payments.push(Payment {
-            amt_msat: payment_info.amt_msat,
-            asset_amount,
-            asset_id,
-            payment_hash: hex_str(&payment_hash.0),
-            inbound: true,
-            status: payment_info.status,
-            created_at: payment_info.created_at,
-            updated_at: payment_info.updated_at,
-            payee_pubkey: payment_info.payee_pubkey.to_string(),
-            preimage: None,
-        });
-    }
-
-    for (payment_id, payment_info) in &outbound_payments {
-        let payment_hash = &PaymentHash(payment_id.0);
-
-        let rgb_payment_info_path_outbound =
-            get_rgb_payment_info_path(payment_hash, &state.static_state.ldk_data_dir, false);
-
-        let (asset_amount, asset_id) = if rgb_payment_info_path_outbound.exists() {
-            let info = parse_rgb_payment_info(&rgb_payment_info_path_outbound);
-            (Some(info.amount), Some(info.contract_id.to_string()))
-        } else {
-            (None, None)
-        };
-
-        let preimage = if matches!(payment_info.status, HTLCStatus::Succeeded) {
-            payment_info.preimage.map(|p| hex_str(&p.0))
-        } else {
-            None
-        };
-
-        payments.push(Payment {
-            amt_msat: payment_info.amt_msat,
-            asset_amount,
-            asset_id,
-            payment_hash: hex_str(&payment_hash.0),
-            inbound: false,
-            status: payment_info.status,
-            created_at: payment_info.created_at,
-            updated_at: payment_info.updated_at,
-            payee_pubkey: payment_info.payee_pubkey.to_string(),
-            preimage,
-        });
-    }
+    let payments = inbound_payments
+        .iter()
+        .map(|(payment_hash, info)| inbound_payment(payment_hash, info, ldk_data_dir))
+        .chain(
+            outbound_payments
+                .iter()
+                .map(|(payment_id, info)| outbound_payment(payment_id, info, ldk_data_dir)),
+        )
+        .collect();
 
     Ok(Json(ListPaymentsResponse { payments }))
 }
@@ -2540,12 +2603,12 @@ pub(crate) async fn list_swaps(
         }
         if status != swap_data.status {
             if taker {
-                unlocked_state.update_taker_swap_status(payment_hash, status.clone());
+                unlocked_state.update_taker_swap_status(payment_hash, status.clone())?;
             } else {
-                unlocked_state.update_maker_swap_status(payment_hash, status.clone());
+                unlocked_state.update_maker_swap_status(payment_hash, status.clone())?;
             }
         }
-        Swap {
+        Ok::<_, APIError>(Swap {
             payment_hash: payment_hash.to_string(),
             qty_from: swap_data.swap_info.qty_from,
             qty_to: swap_data.swap_info.qty_to,
@@ -2556,7 +2619,7 @@ pub(crate) async fn list_swaps(
             initiated_at: swap_data.initiated_at,
             expires_at: swap_data.swap_info.expiry,
             completed_at: swap_data.completed_at,
-        }
+        })
     };
 
     let taker_swaps = unlocked_state.taker_swaps();
@@ -2566,11 +2629,11 @@ pub(crate) async fn list_swaps(
         taker: taker_swaps
             .iter()
             .map(|(ph, sd)| map_swap(ph, sd, true))
-            .collect(),
+            .collect::<Result<_, _>>()?,
         maker: maker_swaps
             .iter()
             .map(|(ph, sd)| map_swap(ph, sd, false))
-            .collect(),
+            .collect::<Result<_, _>>()?,
     }))
 }
 
@@ -2764,7 +2827,7 @@ pub(crate) async fn ln_invoice(
                 status: HTLCStatus::Pending,
                 updated_at: created_at,
             },
-        );
+        )?;
 
         Ok(Json(LNInvoiceResponse {
             invoice: invoice.to_string(),
@@ -2823,7 +2886,8 @@ pub(crate) async fn maker_execute(
             PublicKey::from_str(&payload.taker_pubkey).map_err(|_| APIError::InvalidPubkey)?;
 
         if get_current_timestamp() > swapstring.swap_info.expiry {
-            unlocked_state.update_maker_swap_status(&swapstring.payment_hash, SwapStatus::Expired);
+            unlocked_state
+                .update_maker_swap_status(&swapstring.payment_hash, SwapStatus::Expired)?;
             return Err(APIError::ExpiredSwapOffer);
         }
 
@@ -2984,7 +3048,7 @@ pub(crate) async fn maker_execute(
             );
         }
 
-        unlocked_state.update_maker_swap_status(&swapstring.payment_hash, SwapStatus::Pending);
+        unlocked_state.update_maker_swap_status(&swapstring.payment_hash, SwapStatus::Pending)?;
 
         let payment_hash: PaymentHash = payment_preimage.into();
         let (_status, err) = match unlocked_state
@@ -3010,7 +3074,7 @@ pub(crate) async fn maker_execute(
             None => Ok(Json(EmptyResponse {})),
             Some(e) => {
                 unlocked_state
-                    .update_maker_swap_status(&swapstring.payment_hash, SwapStatus::Failed);
+                    .update_maker_swap_status(&swapstring.payment_hash, SwapStatus::Failed)?;
                 Err(APIError::FailedPayment(format!("{e:?}")))
             }
         }
@@ -3079,7 +3143,7 @@ pub(crate) async fn maker_init(
             .channel_manager
             .create_inbound_payment(Some(DUST_LIMIT_MSAT), payload.timeout_sec, None)
             .unwrap();
-        unlocked_state.add_maker_swap(payment_hash, swap_data);
+        unlocked_state.add_maker_swap(payment_hash, swap_data)?;
 
         let swapstring = SwapString::from_swap_info(&swap_info, payment_hash).to_string();
 
@@ -3152,6 +3216,8 @@ pub(crate) async fn node_info(
     let network_nodes = graph_lock.nodes().len();
     let network_channels = graph_lock.channels().len();
 
+    let ldk_log_stats = state.static_state.logger.stats();
+
     Ok(Json(NodeInfoResponse {
         pubkey: unlocked_state.channel_manager.get_our_node_id().to_string(),
         num_channels: chans.len(),
@@ -3171,6 +3237,11 @@ pub(crate) async fn node_info(
         channel_asset_max_amount: u64::MAX,
         network_nodes,
         network_channels,
+        announce_alias: unlocked_state.announce_alias.clone(),
+        announce_color: unlocked_state.announce_color.clone(),
+        ldk_log_lines_written: ldk_log_stats.lines_written,
+        ldk_log_bytes_written: ldk_log_stats.bytes_written,
+        ldk_log_dropped_lines: ldk_log_stats.dropped_lines,
     }))
 }
 
@@ -3239,32 +3310,41 @@ pub(crate) async fn open_channel(
         }
 
         let (peer_pubkey, mut peer_addr) =
-            parse_peer_info(payload.peer_pubkey_and_opt_addr.to_string())?;
+            parse_peer_info(payload.peer_pubkey_and_opt_addr.to_string()).await?;
 
         let peer_data_path = state.static_state.ldk_data_dir.join(CHANNEL_PEER_DATA);
         if peer_addr.is_none() {
             if let Some(peer) = unlocked_state.peer_manager.peer_by_node_id(&peer_pubkey) {
                 if let Some(socket_address) = peer.socket_address {
-                    if let Ok(mut socket_addrs) = socket_address.to_socket_addrs() {
+                    if let Ok(socket_addrs) = resolve_socket_address(&socket_address).await {
                         // assuming there's only one IP address
-                        peer_addr = socket_addrs.next();
+                        peer_addr = socket_addrs.into_iter().next();
                     }
                 }
             }
         }
+        // an address read from the peer data file is already persisted, possibly as a hostname
+        let mut stored_peer_addr = false;
         if peer_addr.is_none() {
-            let peer_info = disk::read_channel_peer_data(&peer_data_path)?;
-            for (pubkey, addr) in peer_info.into_iter() {
-                if pubkey == peer_pubkey {
-                    peer_addr = Some(addr);
-                    break;
+            let peer_info = disk::read_channel_peer_data(&peer_data_path).await?;
+            if let Some(addr) = peer_info.get(&peer_pubkey) {
+                if let Ok(socket_addrs) = resolve_socket_address(addr).await {
+                    peer_addr = socket_addrs.into_iter().next();
+                    stored_peer_addr = true;
                 }
             }
         }
         if let Some(peer_addr) = peer_addr {
             connect_peer_if_necessary(peer_pubkey, peer_addr, unlocked_state.peer_manager.clone())
                 .await?;
-            disk::persist_channel_peer(&peer_data_path, &peer_pubkey, &peer_addr)?;
+            if !stored_peer_addr {
+                disk::persist_channel_peer(
+                    &peer_data_path,
+                    &peer_pubkey,
+                    &SocketAddress::from(peer_addr),
+                )
+                .await?;
+            }
         } else {
             return Err(APIError::InvalidPeerInfo(s!(
                 "cannot find the address for the provided pubkey"
@@ -3506,8 +3586,9 @@ pub(crate) async fn restore(
             &state.static_state.storage_dir_path,
         )?;
 
-        let _mnemonic =
-            check_password_validity(&payload.password, &state.static_state.storage_dir_path)?;
+        if !verify_password(&payload.password, &state.static_state.storage_dir_path)? {
+            return Err(APIError::WrongPassword);
+        }
 
         Ok(Json(EmptyResponse {}))
     })
@@ -3717,9 +3798,9 @@ pub(crate) async fn send_payment(
                 .pay_for_offer(&offer, Some(amt_msat), payment_id, params);
             if pay.is_err() {
                 tracing::error!("ERROR: failed to pay: {:?}", pay);
-                unlocked_state.update_outbound_payment_status(payment_id, HTLCStatus::Failed);
+                unlocked_state.update_outbound_payment_status(payment_id, HTLCStatus::Failed)?;
                 status = HTLCStatus::Failed;
-                unlocked_state.update_outbound_payment_status(payment_id, status);
+                unlocked_state.update_outbound_payment_status(payment_id, status)?;
             }
             (payment_id, None, secret)
         } else {
@@ -3833,7 +3914,7 @@ pub(crate) async fn send_payment(
                 Err(e) => {
                     tracing::error!("ERROR: failed to send payment: {:?}", e);
                     status = HTLCStatus::Failed;
-                    unlocked_state.update_outbound_payment_status(payment_id, status);
+                    unlocked_state.update_outbound_payment_status(payment_id, status)?;
                 },
             };
 
@@ -3960,7 +4041,7 @@ pub(crate) async fn taker(
         }
 
         let swap_data = SwapData::create_from_swap_info(&swapstring.swap_info);
-        unlocked_state.add_taker_swap(swapstring.payment_hash, swap_data);
+        unlocked_state.add_taker_swap(swapstring.payment_hash, swap_data)?;
 
         Ok(Json(EmptyResponse {}))
     })
@@ -0,0 +1,74 @@
+use std::collections::HashSet;
+
+use lightning::ln::types::ChannelId;
+use lightning::util::hash_tables::new_hash_map;
+
+use crate::ldk::ChannelIdsMap;
+
+#[test]
+fn channel_ids_collisions() {
+    let temporary_channel_id_1 = ChannelId::from_bytes([1; 32]);
+    let temporary_channel_id_2 = ChannelId::from_bytes([2; 32]);
+    let temporary_channel_id_3 = ChannelId::from_bytes([3; 32]);
+    let channel_id_a = ChannelId::from_bytes([10; 32]);
+    let channel_id_b = ChannelId::from_bytes([11; 32]);
+
+    let mut channel_ids_map = ChannelIdsMap {
+        channel_ids: new_hash_map(),
+    };
+    channel_ids_map
+        .channel_ids
+        .insert(temporary_channel_id_1, channel_id_a);
+    channel_ids_map
+        .channel_ids
+        .insert(temporary_channel_id_3, channel_id_b);
+    assert!(channel_ids_map.collisions().is_empty());
+
+    // two temporary channel IDs mapping to the same final channel ID
+    channel_ids_map
+        .channel_ids
+        .insert(temporary_channel_id_2, channel_id_a);
+    let collisions = channel_ids_map.collisions();
+    assert_eq!(collisions.len(), 1);
+    let (channel_id, mut temporary_channel_ids) = collisions[0].clone();
+    assert_eq!(channel_id, channel_id_a);
+    temporary_channel_ids.sort();
+    assert_eq!(
+        temporary_channel_ids,
+        vec![temporary_channel_id_1, temporary_channel_id_2]
+    );
+}
+
+#[test]
+fn channel_ids_reconcile() {
+    let temporary_channel_id_1 = ChannelId::from_bytes([1; 32]);
+    let temporary_channel_id_2 = ChannelId::from_bytes([2; 32]);
+    let temporary_channel_id_3 = ChannelId::from_bytes([3; 32]);
+    let live_channel_id = ChannelId::from_bytes([10; 32]);
+    let dead_channel_id_a = ChannelId::from_bytes([11; 32]);
+    let dead_channel_id_b = ChannelId::from_bytes([12; 32]);
+
+    let mut channel_ids_map = ChannelIdsMap {
+        channel_ids: new_hash_map(),
+    };
+    channel_ids_map
+        .channel_ids
+        .insert(temporary_channel_id_1, live_channel_id);
+    channel_ids_map
+        .channel_ids
+        .insert(temporary_channel_id_2, dead_channel_id_a);
+    channel_ids_map
+        .channel_ids
+        .insert(temporary_channel_id_3, dead_channel_id_b);
+
+    let live_channel_ids = HashSet::from([live_channel_id]);
+    assert_eq!(channel_ids_map.reconcile(&live_channel_ids), 2);
+    assert_eq!(channel_ids_map.channel_ids.len(), 1);
+    assert_eq!(
+        channel_ids_map.channel_ids.get(&temporary_channel_id_1),
+        Some(&live_channel_id)
+    );
+
+    // nothing left to prune
+    assert_eq!(channel_ids_map.reconcile(&live_channel_ids), 0);
+}
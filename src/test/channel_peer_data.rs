@@ -0,0 +1,116 @@
+use bitcoin::secp256k1::{All, PublicKey, Secp256k1, SecretKey};
+use lightning::ln::msgs::SocketAddress;
+
+use crate::disk::{
+    persist_channel_peer, persist_channel_peers, read_channel_peer_data, CHANNEL_PEER_DATA,
+};
+
+use super::*;
+
+const TEST_DIR_BASE: &str = "tmp/channel_peer_data/";
+
+fn _test_peer(secp: &Secp256k1<All>, i: u8) -> (PublicKey, SocketAddress) {
+    let secret_key = SecretKey::from_slice(&[i; 32]).unwrap();
+    let address = SocketAddress::from(SocketAddr::from(([127, 0, 0, 1], 9000 + i as u16)));
+    (PublicKey::from_secret_key(secp, &secret_key), address)
+}
+
+#[serial_test::serial]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[traced_test]
+async fn bulk_import() {
+    let test_dir = format!("{TEST_DIR_BASE}bulk_import/");
+    if Path::new(&test_dir).is_dir() {
+        std::fs::remove_dir_all(&test_dir).unwrap();
+    }
+    std::fs::create_dir_all(&test_dir).unwrap();
+    let peer_data_path = PathBuf::from(&test_dir).join(CHANNEL_PEER_DATA);
+
+    let secp = Secp256k1::new();
+    let (existing_pubkey, existing_address) = _test_peer(&secp, 200);
+    persist_channel_peer(&peer_data_path, &existing_pubkey, &existing_address)
+        .await
+        .unwrap();
+
+    let mut peers: Vec<(PublicKey, SocketAddress)> =
+        (1..=100u8).map(|i| _test_peer(&secp, i)).collect();
+    // a duplicate pubkey, the last address should win
+    let (dup_pubkey, _) = peers[0];
+    let dup_address = SocketAddress::from(SocketAddr::from(([127, 0, 0, 2], 9999)));
+    peers.push((dup_pubkey, dup_address.clone()));
+    persist_channel_peers(&peer_data_path, &peers)
+        .await
+        .unwrap();
+
+    let peer_data = read_channel_peer_data(&peer_data_path).await.unwrap();
+    assert_eq!(peer_data.len(), 101);
+    assert_eq!(peer_data.get(&existing_pubkey), Some(&existing_address));
+    assert_eq!(peer_data.get(&dup_pubkey), Some(&dup_address));
+    for (pubkey, address) in &peers[1..100] {
+        assert_eq!(peer_data.get(pubkey), Some(address));
+    }
+}
+
+#[serial_test::serial]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[traced_test]
+async fn address_types() {
+    let test_dir = format!("{TEST_DIR_BASE}address_types/");
+    if Path::new(&test_dir).is_dir() {
+        std::fs::remove_dir_all(&test_dir).unwrap();
+    }
+    std::fs::create_dir_all(&test_dir).unwrap();
+    let peer_data_path = PathBuf::from(&test_dir).join(CHANNEL_PEER_DATA);
+
+    let secp = Secp256k1::new();
+    let (ipv4_pubkey, ipv4_address) = _test_peer(&secp, 1);
+    let (ipv6_pubkey, _) = _test_peer(&secp, 2);
+    let ipv6_address = SocketAddress::from_str("[2001:db8::1]:9735").unwrap();
+    let (onion_pubkey, _) = _test_peer(&secp, 3);
+    let onion_address = SocketAddress::from_str(
+        "pg6mmjiyjmcrsslvykfwnntlaru7p5svn6y2ymmju6nubxndf4pscryd.onion:9735",
+    )
+    .unwrap();
+    assert!(matches!(onion_address, SocketAddress::OnionV3 { .. }));
+
+    persist_channel_peer(&peer_data_path, &ipv4_pubkey, &ipv4_address)
+        .await
+        .unwrap();
+    persist_channel_peer(&peer_data_path, &ipv6_pubkey, &ipv6_address)
+        .await
+        .unwrap();
+    persist_channel_peer(&peer_data_path, &onion_pubkey, &onion_address)
+        .await
+        .unwrap();
+
+    let peer_data = read_channel_peer_data(&peer_data_path).await.unwrap();
+    assert_eq!(peer_data.len(), 3);
+    assert_eq!(peer_data.get(&ipv4_pubkey), Some(&ipv4_address));
+    assert_eq!(peer_data.get(&ipv6_pubkey), Some(&ipv6_address));
+    assert_eq!(peer_data.get(&onion_pubkey), Some(&onion_address));
+}
+
+#[serial_test::serial]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[traced_test]
+async fn skip_malformed_rows() {
+    let test_dir = format!("{TEST_DIR_BASE}skip_malformed_rows/");
+    if Path::new(&test_dir).is_dir() {
+        std::fs::remove_dir_all(&test_dir).unwrap();
+    }
+    std::fs::create_dir_all(&test_dir).unwrap();
+    let peer_data_path = PathBuf::from(&test_dir).join(CHANNEL_PEER_DATA);
+
+    let secp = Secp256k1::new();
+    let (pubkey, address) = _test_peer(&secp, 1);
+    std::fs::write(
+        &peer_data_path,
+        format!("{pubkey}@{address}\n02abcd@127.0.0.1:9735\n"),
+    )
+    .unwrap();
+
+    let peer_data = read_channel_peer_data(&peer_data_path).await.unwrap();
+    assert_eq!(peer_data.len(), 1);
+    assert_eq!(peer_data.get(&pubkey), Some(&address));
+    assert!(logs_contain("Skipping invalid channel peer entry"));
+}
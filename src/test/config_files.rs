@@ -0,0 +1,32 @@
+use crate::disk::write_file_if_changed;
+
+use super::*;
+
+const TEST_DIR_BASE: &str = "tmp/config_files/";
+
+#[test]
+fn skip_unchanged_config_files() {
+    if Path::new(TEST_DIR_BASE).is_dir() {
+        std::fs::remove_dir_all(TEST_DIR_BASE).unwrap();
+    }
+    std::fs::create_dir_all(TEST_DIR_BASE).unwrap();
+    let path = PathBuf::from(TEST_DIR_BASE).join("indexer_url");
+
+    // missing file gets written
+    assert!(write_file_if_changed(&path, ELECTRUM_URL).unwrap());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), ELECTRUM_URL);
+
+    // same contents are not rewritten
+    let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    assert!(!write_file_if_changed(&path, ELECTRUM_URL).unwrap());
+    assert_eq!(
+        std::fs::metadata(&path).unwrap().modified().unwrap(),
+        modified
+    );
+
+    // changed contents are written
+    let new_indexer_url = "127.0.0.1:60001";
+    assert!(write_file_if_changed(&path, new_indexer_url).unwrap());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), new_indexer_url);
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use crate::utils::{
+    default_indexer_network, default_indexer_url, default_proxy_endpoint, value_or_env_with,
+    warn_indexer_network_mismatch, ELECTRUM_URL_MAINNET, ELECTRUM_URL_REGTEST, ELECTRUM_URL_SIGNET,
+    ELECTRUM_URL_TESTNET, ELECTRUM_URL_TESTNET4, PROXY_ENDPOINT_LOCAL, PROXY_ENDPOINT_PUBLIC,
+};
+
+use super::*;
+
+#[test]
+#[traced_test]
+fn default_indexer_and_proxy() {
+    // defaults per network
+    assert_eq!(
+        default_indexer_url(BitcoinNetwork::Regtest),
+        ELECTRUM_URL_REGTEST
+    );
+    assert_eq!(
+        default_indexer_url(BitcoinNetwork::Signet),
+        ELECTRUM_URL_SIGNET
+    );
+    assert_eq!(
+        default_indexer_url(BitcoinNetwork::Testnet),
+        ELECTRUM_URL_TESTNET
+    );
+    assert_eq!(
+        default_indexer_url(BitcoinNetwork::Testnet4),
+        ELECTRUM_URL_TESTNET4
+    );
+    assert_eq!(
+        default_indexer_url(BitcoinNetwork::Mainnet),
+        ELECTRUM_URL_MAINNET
+    );
+    assert_eq!(
+        default_proxy_endpoint(BitcoinNetwork::Regtest),
+        PROXY_ENDPOINT_LOCAL
+    );
+    assert_eq!(
+        default_proxy_endpoint(BitcoinNetwork::Mainnet),
+        PROXY_ENDPOINT_PUBLIC
+    );
+
+    // implied network of a default indexer
+    assert_eq!(
+        default_indexer_network(ELECTRUM_URL_TESTNET),
+        Some(BitcoinNetwork::Testnet)
+    );
+    assert_eq!(default_indexer_network("127.0.0.1:60001"), None);
+
+    // mismatch warning
+    assert!(!warn_indexer_network_mismatch(
+        ELECTRUM_URL_REGTEST,
+        BitcoinNetwork::Regtest
+    ));
+    assert!(!warn_indexer_network_mismatch(
+        "127.0.0.1:60001",
+        BitcoinNetwork::Mainnet
+    ));
+    assert!(warn_indexer_network_mismatch(
+        ELECTRUM_URL_MAINNET,
+        BitcoinNetwork::Testnet
+    ));
+    assert!(logs_contain("is the default one for"));
+}
+
+#[test]
+#[traced_test]
+fn indexer_url_from_env() {
+    let env_prefix = "RLN_";
+    let env_key = "INDEXER_URL";
+    let env_indexer_url = "127.0.0.1:60001";
+    let mut env: HashMap<&str, &str> = HashMap::new();
+    let value_or_env = |value: Option<String>, env: &HashMap<&str, &str>| {
+        value_or_env_with(value, env_prefix, env_key, |k| {
+            env.get(k).map(|v| v.to_string())
+        })
+    };
+
+    // nothing provided
+    assert_eq!(value_or_env(None, &env), None);
+
+    // env fallback
+    env.insert("RLN_INDEXER_URL", env_indexer_url);
+    assert_eq!(value_or_env(None, &env), Some(s!(env_indexer_url)));
+    assert!(logs_contain("Using RLN_INDEXER_URL from the environment"));
+
+    // provided value takes precedence
+    assert_eq!(
+        value_or_env(Some(s!(ELECTRUM_URL_REGTEST)), &env),
+        Some(s!(ELECTRUM_URL_REGTEST))
+    );
+
+    // empty env value is ignored
+    env.insert("RLN_INDEXER_URL", " ");
+    assert_eq!(value_or_env(None, &env), None);
+}
+
+#[test]
+fn indexer_url_from_env_custom_prefix() {
+    let env_key = "INDEXER_URL";
+    let node_a_indexer_url = "127.0.0.1:60001";
+    let node_b_indexer_url = "127.0.0.1:60002";
+    let mut env: HashMap<&str, &str> = HashMap::new();
+    let value_or_env = |env_prefix: &str, env: &HashMap<&str, &str>| {
+        value_or_env_with(None, env_prefix, env_key, |k| {
+            env.get(k).map(|v| v.to_string())
+        })
+    };
+
+    // variables with other prefixes are not consulted
+    env.insert("RLN_INDEXER_URL", "127.0.0.1:50001");
+    env.insert("NODEB_INDEXER_URL", node_b_indexer_url);
+    assert_eq!(value_or_env("NODEA_", &env), None);
+
+    // each node reads its own prefixed variable
+    env.insert("NODEA_INDEXER_URL", node_a_indexer_url);
+    assert_eq!(value_or_env("NODEA_", &env), Some(s!(node_a_indexer_url)));
+    assert_eq!(value_or_env("NODEB_", &env), Some(s!(node_b_indexer_url)));
+}
@@ -0,0 +1,23 @@
+use crate::disk::map_write_error;
+use crate::error::APIError;
+
+use super::*;
+
+#[test]
+fn disk_full_error() {
+    let path = Path::new("some_file");
+
+    let error = std::io::Error::from(std::io::ErrorKind::StorageFull);
+    assert!(matches!(map_write_error(error, path), APIError::DiskFull(p) if p == path));
+
+    let error = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+    assert!(matches!(map_write_error(error, path), APIError::IO(_)));
+
+    // writing to /dev/full always fails with ENOSPC
+    #[cfg(target_os = "linux")]
+    {
+        let path = Path::new("/dev/full");
+        let result = crate::disk::write_file(path, "data");
+        assert!(matches!(result, Err(APIError::DiskFull(p)) if p == path));
+    }
+}
@@ -0,0 +1,85 @@
+use crate::routes::{HTLCStatus, Payment};
+use crate::utils::payments_csv_rows;
+
+use super::*;
+
+/// Minimal CSV parser supporting quoted fields with escaped quotes and line breaks
+fn _parse_csv(csv: &str) -> Vec<Vec<String>> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = csv.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', _) => in_quotes = !in_quotes,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (c, _) => field.push(c),
+        }
+    }
+    records
+}
+
+fn _payment(inbound: bool, asset_id: Option<&str>) -> Payment {
+    Payment {
+        amt_msat: Some(3000000),
+        asset_amount: asset_id.map(|_| 10),
+        asset_id: asset_id.map(|a| a.to_string()),
+        payment_hash: s!("aa").repeat(32),
+        inbound,
+        status: HTLCStatus::Succeeded,
+        created_at: 1700000000,
+        updated_at: 1700000100,
+        payee_pubkey: s!("02").repeat(33),
+        preimage: if inbound {
+            None
+        } else {
+            Some(s!("bb").repeat(32))
+        },
+    }
+}
+
+#[test]
+fn export_payments_csv() {
+    let payments = vec![
+        _payment(true, None),
+        _payment(false, Some("rgb:id")),
+        _payment(true, Some("with,comma \"and quotes\"\nand a line break")),
+    ];
+
+    let csv = payments_csv_rows(payments.clone()).collect::<String>();
+    let records = _parse_csv(&csv);
+
+    // header plus one record per payment, all with the same columns
+    assert_eq!(records.len(), payments.len() + 1);
+    let header = &records[0];
+    assert_eq!(header.len(), 10);
+    assert!(records.iter().all(|r| r.len() == header.len()));
+    let column = |record: &Vec<String>, name: &str| {
+        record[header.iter().position(|h| h == name).unwrap()].clone()
+    };
+
+    for (record, payment) in records[1..].iter().zip(&payments) {
+        assert_eq!(column(record, "payment_hash"), payment.payment_hash);
+        assert_eq!(column(record, "inbound"), payment.inbound.to_string());
+        assert_eq!(column(record, "status"), "Succeeded");
+        assert_eq!(column(record, "amt_msat"), "3000000");
+        assert_eq!(
+            column(record, "asset_id"),
+            payment.asset_id.clone().unwrap_or_default()
+        );
+        assert_eq!(
+            column(record, "preimage"),
+            payment.preimage.clone().unwrap_or_default()
+        );
+        assert_eq!(column(record, "created_at"), "1700000000");
+    }
+}
@@ -0,0 +1,49 @@
+use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+use lightning::ln::msgs::SocketAddress;
+
+use crate::disk::{persist_channel_peer, read_channel_peer_data, CHANNEL_PEER_DATA};
+
+use super::*;
+
+const TEST_DIR_BASE: &str = "tmp/file_state_lock/";
+
+#[serial_test::serial]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+#[traced_test]
+async fn concurrent_channel_peer_writes() {
+    if Path::new(TEST_DIR_BASE).is_dir() {
+        std::fs::remove_dir_all(TEST_DIR_BASE).unwrap();
+    }
+    std::fs::create_dir_all(TEST_DIR_BASE).unwrap();
+    let peer_data_path = PathBuf::from(TEST_DIR_BASE).join(CHANNEL_PEER_DATA);
+
+    let secp = Secp256k1::new();
+    let peers: Vec<(PublicKey, SocketAddress)> = (1..=20u8)
+        .map(|i| {
+            let secret_key = SecretKey::from_slice(&[i; 32]).unwrap();
+            let address = SocketAddress::from(SocketAddr::from(([127, 0, 0, 1], 9000 + i as u16)));
+            (PublicKey::from_secret_key(&secp, &secret_key), address)
+        })
+        .collect();
+
+    // persist all peers concurrently on the same file
+    let mut handles = vec![];
+    for (pubkey, address) in peers.clone() {
+        let path = peer_data_path.clone();
+        handles.push(tokio::spawn(async move {
+            persist_channel_peer(&path, &pubkey, &address)
+                .await
+                .unwrap();
+        }));
+    }
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    // no update has been lost
+    let peer_data = read_channel_peer_data(&peer_data_path).await.unwrap();
+    assert_eq!(peer_data.len(), peers.len());
+    for (pubkey, address) in peers {
+        assert_eq!(peer_data.get(&pubkey), Some(&address));
+    }
+}
@@ -0,0 +1,23 @@
+use crate::disk::truncate_log_message;
+
+use super::*;
+
+#[test]
+fn truncate_long_log_message() {
+    // short messages are left untouched
+    let message = s!("short message");
+    assert_eq!(truncate_log_message(message.clone(), 8192), message);
+
+    // long messages are truncated to the cap with a marker
+    let message = "a".repeat(10_000);
+    let truncated = truncate_log_message(message, 8192);
+    assert_eq!(
+        truncated,
+        format!("{}\u{2026}[truncated 1808 bytes]", "a".repeat(8192))
+    );
+
+    // truncation never splits a multi-byte char
+    let message = "\u{20ac}".repeat(10);
+    let truncated = truncate_log_message(message, 4);
+    assert_eq!(truncated, "\u{20ac}\u{2026}[truncated 27 bytes]");
+}
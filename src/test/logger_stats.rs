@@ -0,0 +1,47 @@
+use lightning::log_info;
+use lightning::util::logger::Logger;
+
+use crate::disk::{FilesystemLogger, LoggerStats, LDK_LOGS_FILE};
+
+use super::*;
+
+const TEST_DIR_BASE: &str = "tmp/logger_stats/";
+
+#[test]
+fn logger_throughput_counters() {
+    if Path::new(TEST_DIR_BASE).is_dir() {
+        std::fs::remove_dir_all(TEST_DIR_BASE).unwrap();
+    }
+    let logs_dir = PathBuf::from(TEST_DIR_BASE).join("logs");
+    let logger = FilesystemLogger::new_with_logs_dir(logs_dir.clone(), 8192).unwrap();
+    assert_eq!(logger.stats(), LoggerStats::default());
+
+    // written lines and bytes are counted
+    let lines = 10;
+    for i in 0..lines {
+        log_info!(logger, "logger stats test line {i}");
+    }
+    let written = std::fs::metadata(logs_dir.join(LDK_LOGS_FILE))
+        .unwrap()
+        .len();
+    assert_eq!(
+        logger.stats(),
+        LoggerStats {
+            lines_written: lines,
+            bytes_written: written,
+            dropped_lines: 0,
+        }
+    );
+
+    // lines that cannot be written are counted as dropped
+    std::fs::remove_dir_all(&logs_dir).unwrap();
+    log_info!(logger, "logger stats dropped line");
+    assert_eq!(
+        logger.stats(),
+        LoggerStats {
+            lines_written: lines,
+            bytes_written: written,
+            dropped_lines: 1,
+        }
+    );
+}
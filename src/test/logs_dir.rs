@@ -0,0 +1,39 @@
+use lightning::log_info;
+use lightning::util::logger::Logger;
+
+use crate::disk::{daily_file_appender, FilesystemLogger, LDK_LOGS_FILE};
+use crate::error::AppError;
+
+use super::*;
+
+const TEST_DIR_BASE: &str = "tmp/logs_dir/";
+
+#[test]
+fn custom_logs_dir() {
+    if Path::new(TEST_DIR_BASE).is_dir() {
+        std::fs::remove_dir_all(TEST_DIR_BASE).unwrap();
+    }
+    std::fs::create_dir_all(TEST_DIR_BASE).unwrap();
+
+    // logs land in the provided directory
+    let logs_dir = PathBuf::from(TEST_DIR_BASE).join("separate_volume/ldk_logs");
+    let logger = FilesystemLogger::new_with_logs_dir(logs_dir.clone(), 8192).unwrap();
+    log_info!(logger, "custom logs dir test line");
+    let logs = std::fs::read_to_string(logs_dir.join(LDK_LOGS_FILE)).unwrap();
+    assert!(logs.contains("custom logs dir test line"));
+
+    // an unusable logs directory is reported instead of panicking
+    let file_path = PathBuf::from(TEST_DIR_BASE).join("not_a_dir");
+    std::fs::write(&file_path, "").unwrap();
+    let result = FilesystemLogger::new_with_logs_dir(file_path.join("logs"), 8192);
+    assert!(
+        matches!(result, Err(AppError::UnwritableLogsDir(p, _)) if p == file_path.join("logs"))
+    );
+
+    // same for the daemon file appender
+    assert!(daily_file_appender(&logs_dir).is_ok());
+    let result = daily_file_appender(&file_path.join("logs"));
+    assert!(
+        matches!(result, Err(AppError::UnwritableLogsDir(p, _)) if p == file_path.join("logs"))
+    );
+}
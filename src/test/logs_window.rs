@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+
+use crate::disk::{read_logs_between, LDK_LOGS_FILE};
+
+use super::*;
+
+const TEST_DIR_BASE: &str = "tmp/logs_window/";
+
+fn _datetime(datetime: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(datetime).unwrap().to_utc()
+}
+
+#[test]
+fn logs_between() {
+    if Path::new(TEST_DIR_BASE).is_dir() {
+        std::fs::remove_dir_all(TEST_DIR_BASE).unwrap();
+    }
+    let logs_dir = PathBuf::from(TEST_DIR_BASE).join("logs");
+    std::fs::create_dir_all(&logs_dir).unwrap();
+
+    // a rotated file with older lines, a line without a timestamp opens it
+    std::fs::write(
+        logs_dir.join(format!("{LDK_LOGS_FILE}.1")),
+        "orphan continuation line\n\
+        2024-05-01 14:20:00.000 INFO  [mod:1] rotated before window\n\
+        2024-05-01 14:29:59.999 INFO  [mod:2] rotated in window\n\
+        rotated continuation line\n",
+    )
+    .unwrap();
+    std::fs::write(
+        logs_dir.join(LDK_LOGS_FILE),
+        "2024-05-01 14:30:00.000 DEBUG [mod:3] current in window\n\
+        current continuation line\n\
+        2024-05-01 14:40:00.000 WARN  [mod:4] current at window end\n\
+        2024-05-01 14:40:00.001 ERROR [mod:5] current after window\n\
+        after window continuation line\n",
+    )
+    .unwrap();
+    // other files in the logs dir are ignored
+    std::fs::write(
+        logs_dir.join("rln.log.2024-05-01"),
+        "2024-05-01 14:35:00.000 INFO  [mod:6] daemon log\n",
+    )
+    .unwrap();
+
+    let lines = read_logs_between(
+        &logs_dir,
+        _datetime("2024-05-01T14:25:00Z"),
+        _datetime("2024-05-01T14:40:00Z"),
+    )
+    .unwrap();
+    assert_eq!(
+        lines,
+        vec![
+            "2024-05-01 14:29:59.999 INFO  [mod:2] rotated in window",
+            "rotated continuation line",
+            "2024-05-01 14:30:00.000 DEBUG [mod:3] current in window",
+            "current continuation line",
+            "2024-05-01 14:40:00.000 WARN  [mod:4] current at window end",
+        ]
+    );
+
+    // a window with no lines
+    let lines = read_logs_between(
+        &logs_dir,
+        _datetime("2024-05-02T00:00:00Z"),
+        _datetime("2024-05-02T01:00:00Z"),
+    )
+    .unwrap();
+    assert!(lines.is_empty());
+}
@@ -0,0 +1,78 @@
+use crate::error::APIError;
+use crate::utils::{
+    check_password_validity, encrypt_and_save_mnemonic, get_mnemonic_path, verify_password,
+};
+
+use super::*;
+
+const TEST_DIR_BASE: &str = "tmp/mnemonic_encryption/";
+const MNEMONIC: &str =
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+fn _setup_storage_dir(name: &str) -> PathBuf {
+    let storage_dir_path = PathBuf::from(format!("{TEST_DIR_BASE}{name}"));
+    if storage_dir_path.is_dir() {
+        std::fs::remove_dir_all(&storage_dir_path).unwrap();
+    }
+    std::fs::create_dir_all(&storage_dir_path).unwrap();
+    storage_dir_path
+}
+
+#[test]
+fn verify_password_check() {
+    let storage_dir_path = _setup_storage_dir("verify_password");
+    let password = "testpassword";
+
+    // not initialized yet
+    let result = verify_password(password, &storage_dir_path);
+    assert!(matches!(result, Err(APIError::NotInitialized)));
+
+    encrypt_and_save_mnemonic(
+        s!(password),
+        MNEMONIC,
+        &get_mnemonic_path(&storage_dir_path),
+    )
+    .unwrap();
+
+    assert!(verify_password(password, &storage_dir_path).unwrap());
+    assert!(!verify_password("wrongpassword", &storage_dir_path).unwrap());
+}
+
+#[test]
+fn decrypt_mnemonic() {
+    let storage_dir_path = _setup_storage_dir("decrypt_mnemonic");
+    let password = "testpassword";
+
+    encrypt_and_save_mnemonic(
+        s!(password),
+        MNEMONIC,
+        &get_mnemonic_path(&storage_dir_path),
+    )
+    .unwrap();
+
+    let mnemonic = check_password_validity(password, &storage_dir_path).unwrap();
+    assert_eq!(mnemonic.to_string(), MNEMONIC);
+    let result = check_password_validity("wrongpassword", &storage_dir_path);
+    assert!(matches!(result, Err(APIError::WrongPassword)));
+}
+
+#[test]
+fn durable_mnemonic_write() {
+    let storage_dir_path = _setup_storage_dir("durable_mnemonic_write");
+    let mnemonic_path = get_mnemonic_path(&storage_dir_path);
+
+    encrypt_and_save_mnemonic(s!("testpassword"), MNEMONIC, &mnemonic_path).unwrap();
+
+    // only the mnemonic file is left behind, no temp files
+    let entries: Vec<_> = std::fs::read_dir(&storage_dir_path)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .collect();
+    assert_eq!(entries, vec![mnemonic_path.clone()]);
+
+    // overwriting (e.g. on password change) replaces the file content
+    encrypt_and_save_mnemonic(s!("newpassword"), MNEMONIC, &mnemonic_path).unwrap();
+    let mnemonic = check_password_validity("newpassword", &storage_dir_path).unwrap();
+    assert_eq!(mnemonic.to_string(), MNEMONIC);
+    assert!(!verify_password("testpassword", &storage_dir_path).unwrap());
+}
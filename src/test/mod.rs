@@ -46,8 +46,8 @@ use crate::routes::{
     Transaction, Transfer, UnlockRequest, Unspent, WitnessData, HTLC_MIN_MSAT,
 };
 use crate::utils::{
-    hex_str, hex_str_to_vec, validate_and_parse_payment_hash, ELECTRUM_URL_REGTEST, LDK_DIR,
-    PROXY_ENDPOINT_LOCAL,
+    hex_str, hex_str_to_vec, validate_and_parse_payment_hash, LockGuard, ELECTRUM_URL_REGTEST,
+    LDK_DIR, PROXY_ENDPOINT_LOCAL,
 };
 
 use super::*;
@@ -73,6 +73,9 @@ impl Default for UserArgs {
             daemon_listening_port: 3001,
             ldk_peer_listening_port: 9735,
             max_media_upload_size_mb: 3,
+            max_log_line_bytes: 8192,
+            logs_dir: None,
+            env_prefix: s!("RLN_"),
             root_public_key: None,
         }
     }
@@ -162,7 +165,8 @@ async fn start_daemon(
         ..Default::default()
     };
     tokio::spawn(async move {
-        let (router, app_state) = app(args).await.unwrap();
+        let storage_dir_lock = LockGuard::acquire(&args.storage_dir_path).unwrap();
+        let (router, app_state) = app(args, storage_dir_lock).await.unwrap();
         axum::serve(listener, router)
             .with_graceful_shutdown(shutdown_signal(app_state))
             .await
@@ -1621,6 +1625,7 @@ fn unlock_req(password: &str) -> UnlockRequest {
         proxy_endpoint: Some(PROXY_ENDPOINT_LOCAL.to_string()),
         announce_addresses: vec![],
         announce_alias: Some(s!("RLN_alias")),
+        announce_color: None,
     }
 }
 
@@ -1901,6 +1906,8 @@ pub fn mock_fee(fee: u32) -> u32 {
 
 mod authentication;
 mod backup_and_restore;
+mod channel_ids_map;
+mod channel_peer_data;
 mod close_coop_nobtc_acceptor;
 mod close_coop_other_side;
 mod close_coop_standard;
@@ -1911,7 +1918,12 @@ mod close_force_other_side;
 mod close_force_standard;
 mod concurrent_btc_payments;
 mod concurrent_openchannel;
+mod config_files;
+mod default_indexer;
+mod disk_full;
+mod export_payments;
 mod fail_transfers;
+mod file_state_lock;
 mod getchannelid;
 mod hodl_invoice;
 mod htlc_amount_checks;
@@ -1919,17 +1931,29 @@ mod init;
 mod invoice;
 mod issue;
 mod lock_unlock_changepassword;
+mod log_line_length;
+mod logger_stats;
+mod logs_dir;
+mod logs_window;
+mod mnemonic_encryption;
 mod multi_hop;
 mod multi_open_close;
+mod node_announcement;
 mod open_after_double_send;
 mod openchannel_fail;
 mod openchannel_optional_addr;
 mod payment;
+mod peer_host_resolution;
 mod refuse_high_fees;
 mod restart;
+mod revocation_id;
+mod safe_join;
+mod scorer_snapshot;
 mod send_receive;
+mod storage_dir_lock;
 mod swap_assets_liquidity_both_ways;
 mod swap_reverse_same_channel;
+mod swap_role;
 mod swap_roundtrip_assets;
 mod swap_roundtrip_buy;
 mod swap_roundtrip_buy_same_channel;
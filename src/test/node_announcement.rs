@@ -0,0 +1,96 @@
+use crate::utils::{validate_and_parse_announce_alias, validate_and_parse_announce_color};
+
+use super::*;
+
+#[test]
+fn announce_alias_and_color() {
+    // valid alias and color
+    let alias = validate_and_parse_announce_alias("RLN_alias").unwrap();
+    assert_eq!(&alias[..9], b"RLN_alias");
+    assert!(alias[9..].iter().all(|b| *b == 0));
+    let alias = validate_and_parse_announce_alias(&"a".repeat(32)).unwrap();
+    assert_eq!(alias, [b'a'; 32]);
+    let color = validate_and_parse_announce_color("3399fF").unwrap();
+    assert_eq!(color, [0x33, 0x99, 0xff]);
+
+    // fail: alias longer than 32 bytes
+    let err = validate_and_parse_announce_alias(&"a".repeat(33)).unwrap_err();
+    assert!(matches!(err, APIError::InvalidAnnounceAlias(_)));
+    let err = validate_and_parse_announce_alias(&"é".repeat(17)).unwrap_err();
+    assert!(matches!(err, APIError::InvalidAnnounceAlias(_)));
+
+    // fail: malformed color
+    for color in ["", "3399f", "3399ff0", "#3399f", "3399fg"] {
+        let err = validate_and_parse_announce_color(color).unwrap_err();
+        assert!(matches!(err, APIError::InvalidAnnounceColor(_)));
+    }
+}
+
+const TEST_DIR_BASE: &str = "tmp/node_announcement/";
+
+async fn unlock_with_announce(
+    node_address: SocketAddr,
+    password: &str,
+    announce_alias: Option<&str>,
+    announce_color: Option<&str>,
+) {
+    let payload = UnlockRequest {
+        announce_alias: announce_alias.map(|a| a.to_string()),
+        announce_color: announce_color.map(|c| c.to_string()),
+        ..unlock_req(password)
+    };
+    let res = reqwest::Client::new()
+        .post(format!("http://{node_address}/unlock"))
+        .json(&payload)
+        .send()
+        .await
+        .unwrap();
+    _check_response_is_ok(res)
+        .await
+        .json::<EmptyResponse>()
+        .await
+        .unwrap();
+}
+
+#[serial_test::serial]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[traced_test]
+async fn persisted_alias_and_color() {
+    initialize();
+
+    let test_dir_node1 = format!("{TEST_DIR_BASE}node1");
+    let (node1_addr, node1_password) = start_node(&test_dir_node1, NODE1_PEER_PORT, false).await;
+
+    // alias from the unlock request, no color set yet
+    let node1_info = node_info(node1_addr).await;
+    assert_eq!(node1_info.announce_alias, Some(s!("RLN_alias")));
+    assert_eq!(node1_info.announce_color, None);
+
+    // set a color
+    lock(node1_addr).await;
+    unlock_with_announce(node1_addr, &node1_password, None, Some("3399FF")).await;
+    let node1_info = node_info(node1_addr).await;
+    assert_eq!(node1_info.announce_alias, Some(s!("RLN_alias")));
+    assert_eq!(node1_info.announce_color, Some(s!("3399ff")));
+
+    // values are kept when omitted from the unlock request, even across restarts
+    shutdown(&[node1_addr]).await;
+    let node1_addr = start_daemon(&test_dir_node1, NODE1_PEER_PORT, None, true).await;
+    unlock_with_announce(node1_addr, &node1_password, None, None).await;
+    let node1_info = node_info(node1_addr).await;
+    assert_eq!(node1_info.announce_alias, Some(s!("RLN_alias")));
+    assert_eq!(node1_info.announce_color, Some(s!("3399ff")));
+
+    // provided values replace the persisted ones
+    lock(node1_addr).await;
+    unlock_with_announce(
+        node1_addr,
+        &node1_password,
+        Some("new_alias"),
+        Some("000000"),
+    )
+    .await;
+    let node1_info = node_info(node1_addr).await;
+    assert_eq!(node1_info.announce_alias, Some(s!("new_alias")));
+    assert_eq!(node1_info.announce_color, Some(s!("000000")));
+}
@@ -0,0 +1,54 @@
+use lightning::ln::msgs::SocketAddress;
+
+use crate::error::APIError;
+use crate::utils::{resolve_peer_addr, resolve_socket_address};
+
+use super::*;
+
+#[tokio::test]
+async fn peer_host_resolution() {
+    // IP literal
+    let addr = resolve_peer_addr("127.0.0.1:9735").await.unwrap();
+    assert_eq!(addr, SocketAddr::from(([127, 0, 0, 1], 9735)));
+
+    // resolvable hostname
+    let addr = resolve_peer_addr("localhost:9735").await.unwrap();
+    assert!(addr.ip().is_loopback());
+    assert_eq!(addr.port(), 9735);
+
+    // unresolvable hostname
+    let result = resolve_peer_addr("unresolvable.invalid:9735").await;
+    assert!(
+        matches!(result, Err(APIError::PeerHostUnresolvable(h)) if h == "unresolvable.invalid")
+    );
+
+    // malformed addresses
+    let result = resolve_peer_addr("localhost").await;
+    assert!(matches!(result, Err(APIError::InvalidPeerInfo(_))));
+    let result = resolve_peer_addr("localhost:notaport").await;
+    assert!(matches!(result, Err(APIError::InvalidPeerInfo(_))));
+}
+
+#[tokio::test]
+async fn stored_peer_address_resolution() {
+    // IP address
+    let address = SocketAddress::from_str("127.0.0.1:9735").unwrap();
+    let addrs = resolve_socket_address(&address).await.unwrap();
+    assert_eq!(addrs, vec![SocketAddr::from(([127, 0, 0, 1], 9735))]);
+
+    // resolvable hostname
+    let address = SocketAddress::from_str("localhost:9735").unwrap();
+    assert!(matches!(address, SocketAddress::Hostname { .. }));
+    let addrs = resolve_socket_address(&address).await.unwrap();
+    assert!(!addrs.is_empty());
+    assert!(addrs
+        .iter()
+        .all(|a| a.ip().is_loopback() && a.port() == 9735));
+
+    // unresolvable hostname
+    let address = SocketAddress::from_str("unresolvable.invalid:9735").unwrap();
+    let result = resolve_socket_address(&address).await;
+    assert!(
+        matches!(result, Err(APIError::PeerHostUnresolvable(h)) if h == "unresolvable.invalid")
+    );
+}
@@ -0,0 +1,27 @@
+use crate::auth::RevocationId;
+
+use super::*;
+
+#[test]
+fn revocation_id_hex() {
+    // valid values round-trip
+    let hex = "00ff10abcdef";
+    let revocation_id = RevocationId::from_hex(hex).unwrap();
+    assert_eq!(revocation_id.to_hex(), hex);
+    assert_eq!(
+        RevocationId::from_hex("00FF10ABCDEF").unwrap(),
+        revocation_id
+    );
+    assert_eq!(
+        RevocationId::from(vec![0x00, 0xff, 0x10, 0xab, 0xcd, 0xef]),
+        revocation_id
+    );
+
+    // invalid hex is rejected
+    for invalid_hex in ["", "abc", "zz", "0x00ff", "00 ff"] {
+        assert!(
+            RevocationId::from_hex(invalid_hex).is_none(),
+            "{invalid_hex:?} not rejected"
+        );
+    }
+}
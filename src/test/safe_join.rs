@@ -0,0 +1,38 @@
+use crate::error::APIError;
+use crate::utils::safe_join;
+
+use super::*;
+
+#[test]
+fn safe_join_components() {
+    let base = Path::new("storage/media_files");
+
+    // plain file names are joined
+    assert_eq!(
+        safe_join(base, "abcdef0123").unwrap(),
+        base.join("abcdef0123")
+    );
+    assert_eq!(
+        safe_join(base, "file.name").unwrap(),
+        base.join("file.name")
+    );
+
+    // traversal attempts and non-file components are rejected
+    for component in [
+        "",
+        ".",
+        "..",
+        "../mnemonic",
+        "../../etc/passwd",
+        "dir/file",
+        "file/",
+        "/etc/passwd",
+        "..\\mnemonic",
+    ] {
+        let result = safe_join(base, component);
+        assert!(
+            matches!(result, Err(APIError::InvalidPath(ref c)) if c == component),
+            "{component:?} not rejected"
+        );
+    }
+}
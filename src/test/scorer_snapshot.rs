@@ -0,0 +1,143 @@
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bitcoin::constants::ChainHash;
+use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+use bitcoin::Network;
+use lightning::ln::msgs::UnsignedChannelUpdate;
+use lightning::routing::gossip::NodeId;
+use lightning::routing::router::{Path as RoutePath, RouteHop};
+use lightning::routing::scoring::{
+    ProbabilisticScorer, ProbabilisticScoringDecayParameters, ScoreUpdate,
+};
+use lightning::types::features::{ChannelFeatures, NodeFeatures};
+use lightning::util::ser::Writeable;
+
+use crate::disk::{export_scorer, import_scorer, read_scorer, FilesystemLogger, SCORER_FNAME};
+use crate::ldk::NetworkGraph;
+
+use super::*;
+
+const TEST_DIR_BASE: &str = "tmp/scorer_snapshot/";
+
+const SHORT_CHANNEL_ID: u64 = 42;
+const CAPACITY_SAT: u64 = 1_000_000;
+
+fn _network_graph(
+    logger: Arc<FilesystemLogger>,
+    node_1: &PublicKey,
+    node_2: &PublicKey,
+    timestamp: u64,
+) -> Arc<NetworkGraph> {
+    let graph = NetworkGraph::new(Network::Regtest, logger);
+    graph
+        .add_channel_from_partial_announcement(
+            SHORT_CHANNEL_ID,
+            Some(CAPACITY_SAT),
+            timestamp,
+            ChannelFeatures::empty(),
+            NodeId::from_pubkey(node_1),
+            NodeId::from_pubkey(node_2),
+        )
+        .unwrap();
+    // the scorer only learns about channels with known directions
+    for channel_flags in [0, 1] {
+        graph
+            .update_channel_unsigned(&UnsignedChannelUpdate {
+                chain_hash: ChainHash::using_genesis_block(Network::Regtest),
+                short_channel_id: SHORT_CHANNEL_ID,
+                timestamp: timestamp as u32,
+                message_flags: 1,
+                channel_flags,
+                cltv_expiry_delta: 144,
+                htlc_minimum_msat: 1,
+                htlc_maximum_msat: CAPACITY_SAT * 1000,
+                fee_base_msat: 1000,
+                fee_proportional_millionths: 1,
+                excess_data: vec![],
+            })
+            .unwrap();
+    }
+    Arc::new(graph)
+}
+
+#[test]
+fn scorer_snapshot_round_trip() {
+    if Path::new(TEST_DIR_BASE).is_dir() {
+        std::fs::remove_dir_all(TEST_DIR_BASE).unwrap();
+    }
+    let source_dir = PathBuf::from(TEST_DIR_BASE).join("source");
+    let target_dir = PathBuf::from(TEST_DIR_BASE).join("target");
+    std::fs::create_dir_all(&source_dir).unwrap();
+    std::fs::create_dir_all(&target_dir).unwrap();
+    let source_path = source_dir.join(SCORER_FNAME);
+    let target_path = target_dir.join(SCORER_FNAME);
+
+    let logger = Arc::new(FilesystemLogger::new(PathBuf::from(TEST_DIR_BASE), 8192).unwrap());
+    let secp = Secp256k1::new();
+    let mut nodes = [1, 2]
+        .map(|i| PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&[i; 32]).unwrap()));
+    nodes.sort();
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    let graph = _network_graph(logger.clone(), &nodes[0], &nodes[1], now.as_secs());
+    let target = NodeId::from_pubkey(&nodes[1]);
+
+    // teach the scorer about the channel liquidity and persist it
+    let params = ProbabilisticScoringDecayParameters::default();
+    let mut scorer = ProbabilisticScorer::new(params, graph.clone(), logger.clone());
+    assert!(scorer
+        .estimated_channel_liquidity_range(SHORT_CHANNEL_ID, &target)
+        .is_none());
+    let path = RoutePath {
+        hops: vec![RouteHop {
+            pubkey: nodes[1],
+            node_features: NodeFeatures::empty(),
+            short_channel_id: SHORT_CHANNEL_ID,
+            channel_features: ChannelFeatures::empty(),
+            fee_msat: 300_000_000,
+            cltv_expiry_delta: 144,
+            maybe_announced_channel: true,
+        }],
+        blinded_tail: None,
+    };
+    scorer.payment_path_successful(&path, now);
+    let liquidity_range = scorer.estimated_channel_liquidity_range(SHORT_CHANNEL_ID, &target);
+    assert!(liquidity_range.is_some());
+    std::fs::write(&source_path, scorer.encode()).unwrap();
+
+    // an imported snapshot reproduces the exported scorer
+    let mut snapshot = vec![];
+    export_scorer(&source_path, &mut snapshot).unwrap();
+    import_scorer(
+        &target_path,
+        &mut &snapshot[..],
+        graph.clone(),
+        logger.clone(),
+    )
+    .unwrap();
+    let imported = read_scorer(&target_path, graph.clone(), logger.clone());
+    assert_eq!(
+        imported.estimated_channel_liquidity_range(SHORT_CHANNEL_ID, &target),
+        liquidity_range
+    );
+    assert_eq!(imported.encode(), scorer.encode());
+
+    // invalid snapshots are refused and leave the existing scorer untouched
+    let mut wrong_magic = snapshot.clone();
+    wrong_magic[0] ^= 0xff;
+    let mut wrong_version = snapshot.clone();
+    wrong_version[4] += 1;
+    let truncated = snapshot[..snapshot.len() - 1].to_vec();
+    let mut trailing = snapshot.clone();
+    trailing.push(0);
+    for invalid_snapshot in [vec![], wrong_magic, wrong_version, truncated, trailing] {
+        let res = import_scorer(
+            &target_path,
+            &mut &invalid_snapshot[..],
+            graph.clone(),
+            logger.clone(),
+        );
+        assert!(matches!(res, Err(APIError::InvalidScorerSnapshot(_))));
+        assert_eq!(std::fs::read(&target_path).unwrap(), scorer.encode());
+    }
+}
@@ -0,0 +1,29 @@
+use crate::error::AppError;
+use crate::utils::LockGuard;
+
+use super::*;
+
+const TEST_DIR_BASE: &str = "tmp/storage_dir_lock/";
+
+#[test]
+fn storage_dir_lock() {
+    if Path::new(TEST_DIR_BASE).is_dir() {
+        std::fs::remove_dir_all(TEST_DIR_BASE).unwrap();
+    }
+    let storage_dir_path = PathBuf::from(TEST_DIR_BASE);
+
+    // the storage directory is created if missing
+    let guard = LockGuard::acquire(&storage_dir_path).unwrap();
+    assert!(storage_dir_path.is_dir());
+    drop(guard);
+
+    let guard = LockGuard::acquire(&storage_dir_path).unwrap();
+
+    // a second guard on the same storage directory cannot be acquired
+    let result = LockGuard::acquire(&storage_dir_path);
+    assert!(matches!(result, Err(AppError::DataDirInUse(p)) if p == storage_dir_path));
+
+    // the lock is released on drop
+    drop(guard);
+    let _guard = LockGuard::acquire(&storage_dir_path).unwrap();
+}
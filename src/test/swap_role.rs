@@ -0,0 +1,65 @@
+use lightning::types::payment::PaymentHash;
+use lightning::util::hash_tables::new_hash_map;
+use lightning::util::ser::Writeable;
+
+use crate::disk::read_swaps_info;
+use crate::ldk::{SwapMap, SwapRole};
+use crate::swap::{SwapData, SwapInfo};
+
+use super::*;
+
+const TEST_DIR_BASE: &str = "tmp/swap_role/";
+
+fn swap_data(qty_from: u64) -> SwapData {
+    SwapData::create_from_swap_info(&SwapInfo {
+        qty_from,
+        qty_to: 10,
+        from_asset: None,
+        to_asset: None,
+        expiry: 3600,
+    })
+}
+
+#[test]
+fn swap_role_from_path() {
+    let test_dir = PathBuf::from(TEST_DIR_BASE);
+    let _ = std::fs::remove_dir_all(&test_dir);
+    std::fs::create_dir_all(&test_dir).unwrap();
+
+    let mut maker_swaps = SwapMap {
+        role: SwapRole::Maker,
+        swaps: new_hash_map(),
+    };
+    maker_swaps
+        .swaps
+        .insert(PaymentHash([1; 32]), swap_data(1000));
+    std::fs::write(test_dir.join(SwapRole::Maker.fname()), maker_swaps.encode()).unwrap();
+
+    let mut taker_swaps = SwapMap {
+        role: SwapRole::Taker,
+        swaps: new_hash_map(),
+    };
+    taker_swaps
+        .swaps
+        .insert(PaymentHash([2; 32]), swap_data(2000));
+    taker_swaps
+        .swaps
+        .insert(PaymentHash([3; 32]), swap_data(3000));
+    std::fs::write(test_dir.join(SwapRole::Taker.fname()), taker_swaps.encode()).unwrap();
+
+    let maker_swaps = read_swaps_info(&test_dir, SwapRole::Maker);
+    assert_eq!(maker_swaps.role, SwapRole::Maker);
+    assert_eq!(maker_swaps.swaps.len(), 1);
+    assert!(maker_swaps.swaps.contains_key(&PaymentHash([1; 32])));
+
+    let taker_swaps = read_swaps_info(&test_dir, SwapRole::Taker);
+    assert_eq!(taker_swaps.role, SwapRole::Taker);
+    assert_eq!(taker_swaps.swaps.len(), 2);
+    assert!(taker_swaps.swaps.contains_key(&PaymentHash([2; 32])));
+
+    // a missing file yields an empty map that keeps the requested role
+    std::fs::remove_file(test_dir.join(SwapRole::Taker.fname())).unwrap();
+    let taker_swaps = read_swaps_info(&test_dir, SwapRole::Taker);
+    assert_eq!(taker_swaps.role, SwapRole::Taker);
+    assert!(taker_swaps.swaps.is_empty());
+}
@@ -111,3 +111,32 @@ async fn fail() {
     let api_error_response = res.text().await.unwrap();
     assert_eq!(api_error_response, "length limit exceeded");
 }
+
+#[serial_test::serial]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[traced_test]
+async fn fail_path_traversal() {
+    initialize();
+
+    let test_dir_base = format!("{TEST_DIR_BASE}fail_path_traversal/");
+    let test_dir_node1 = format!("{test_dir_base}node1");
+    let (node1_addr, _) = start_node(&test_dir_node1, NODE1_PEER_PORT, false).await;
+
+    // request asset media with a digest escaping the media directory
+    let payload = GetAssetMediaRequest {
+        digest: s!("../../mnemonic"),
+    };
+    let res = reqwest::Client::new()
+        .post(format!("http://{node1_addr}/getassetmedia"))
+        .json(&payload)
+        .send()
+        .await
+        .unwrap();
+    check_response_is_nok(
+        res,
+        reqwest::StatusCode::BAD_REQUEST,
+        "Invalid path component: ../../mnemonic",
+        "InvalidPath",
+    )
+    .await;
+}
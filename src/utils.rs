@@ -3,9 +3,13 @@ use bitcoin::hashes::sha256::Hash as Sha256;
 use bitcoin::hashes::Hash;
 use bitcoin::io;
 use bitcoin::secp256k1::PublicKey;
+use fs2::FileExt;
 use futures::Future;
 use lightning::ln::channel_state::ChannelDetails;
+use lightning::ln::channelmanager::PaymentId;
+use lightning::ln::msgs::SocketAddress;
 use lightning::ln::types::ChannelId;
+use lightning::rgb_utils::{get_rgb_payment_info_path, parse_rgb_payment_info};
 use lightning::routing::router::{
     Payee, PaymentParameters, Route, RouteHint, RouteParameters, Router as _,
     DEFAULT_MAX_TOTAL_CLTV_EXPIRY_DELTA, MAX_PATH_LENGTH_ESTIMATE,
@@ -25,30 +29,34 @@ use std::{
     fs,
     net::{SocketAddr, TcpStream, ToSocketAddrs},
     path::Path,
-    path::PathBuf,
+    path::{Component, PathBuf},
     str::FromStr,
     sync::{Arc, Mutex, MutexGuard},
     time::{Duration, SystemTime},
 };
+use tempfile::NamedTempFile;
 use tokio::sync::{Mutex as TokioMutex, MutexGuard as TokioMutexGuard};
 use tokio_util::sync::CancellationToken;
+use zeroize::Zeroizing;
 
 use crate::ldk::{ChannelIdsMap, Router};
 use crate::rgb::{get_rgb_channel_info_optional, RgbLibWalletWrapper};
-use crate::routes::{DEFAULT_FINAL_CLTV_EXPIRY_DELTA, HTLC_MIN_MSAT};
+use crate::routes::{HTLCStatus, Payment, DEFAULT_FINAL_CLTV_EXPIRY_DELTA, HTLC_MIN_MSAT};
 use crate::{
     args::UserArgs,
-    disk::FilesystemLogger,
+    auth::RevocationId,
+    disk::{map_write_error, FilesystemLogger},
     error::{APIError, AppError},
     ldk::{
         BumpTxEventHandler, ChainMonitor, ChannelManager, InboundPaymentInfoStorage,
         LdkBackgroundServices, NetworkGraph, OnionMessenger, OutboundPaymentInfoStorage,
-        OutputSweeper, PeerManager, SwapMap,
+        OutputSweeper, PaymentInfo, PeerManager, SwapMap,
     },
 };
 
 pub(crate) const LDK_DIR: &str = ".ldk";
 pub(crate) const LOGS_DIR: &str = "logs";
+pub(crate) const NODE_LOCK_FNAME: &str = ".node.lock";
 pub(crate) const ELECTRUM_URL_REGTEST: &str = "127.0.0.1:50001";
 pub(crate) const ELECTRUM_URL_SIGNET: &str = "ssl://electrum.iriswallet.com:50033";
 pub(crate) const ELECTRUM_URL_TESTNET: &str = "ssl://electrum.iriswallet.com:50013";
@@ -56,6 +64,8 @@ pub(crate) const ELECTRUM_URL_TESTNET4: &str = "ssl://electrum.iriswallet.com:50
 pub(crate) const ELECTRUM_URL_MAINNET: &str = "ssl://electrum.iriswallet.com:50003";
 pub(crate) const PROXY_ENDPOINT_LOCAL: &str = "rpc://127.0.0.1:3000/json-rpc";
 pub(crate) const PROXY_ENDPOINT_PUBLIC: &str = "rpcs://proxy.iriswallet.com/0.2/json-rpc";
+pub(crate) const INDEXER_URL_ENV_KEY: &str = "INDEXER_URL";
+pub(crate) const PROXY_ENDPOINT_ENV_KEY: &str = "PROXY_ENDPOINT";
 const PASSWORD_MIN_LENGTH: u8 = 8;
 
 pub(crate) struct AppState {
@@ -65,7 +75,8 @@ pub(crate) struct AppState {
     pub(crate) ldk_background_services: Arc<Mutex<Option<LdkBackgroundServices>>>,
     pub(crate) changing_state: Mutex<bool>,
     pub(crate) root_public_key: Option<biscuit_auth::PublicKey>,
-    pub(crate) revoked_tokens: Arc<Mutex<HashSet<Vec<u8>>>>,
+    pub(crate) revoked_tokens: Arc<Mutex<HashSet<RevocationId>>>,
+    pub(crate) storage_dir_lock: Mutex<Option<LockGuard>>,
 }
 
 impl AppState {
@@ -93,6 +104,7 @@ pub(crate) struct StaticState {
     pub(crate) ldk_data_dir: PathBuf,
     pub(crate) logger: Arc<FilesystemLogger>,
     pub(crate) max_media_upload_size_mb: u16,
+    pub(crate) env_prefix: String,
 }
 
 pub(crate) struct UnlockedAppState {
@@ -114,6 +126,8 @@ pub(crate) struct UnlockedAppState {
     pub(crate) rgb_send_lock: Arc<Mutex<bool>>,
     pub(crate) channel_ids_map: Arc<Mutex<ChannelIdsMap>>,
     pub(crate) proxy_endpoint: String,
+    pub(crate) announce_alias: Option<String>,
+    pub(crate) announce_color: Option<String>,
 }
 
 impl UnlockedAppState {
@@ -184,6 +198,7 @@ pub(crate) fn check_password_validity(
         let mcrypt = new_magic_crypt!(password, 256);
         let mnemonic_str = mcrypt
             .decrypt_base64_to_string(encrypted_mnemonic)
+            .map(Zeroizing::new)
             .map_err(|_| APIError::WrongPassword)?;
         Ok(Mnemonic::from_str(&mnemonic_str).expect("valid mnemonic"))
     } else {
@@ -191,6 +206,22 @@ pub(crate) fn check_password_validity(
     }
 }
 
+/// Check the given password can decrypt the mnemonic, without parsing it.
+///
+/// The decrypted bytes are zeroized as soon as the check is done.
+pub(crate) fn verify_password(password: &str, storage_dir_path: &Path) -> Result<bool, APIError> {
+    let mnemonic_path = get_mnemonic_path(storage_dir_path);
+    if let Ok(encrypted_mnemonic) = fs::read_to_string(mnemonic_path) {
+        let mcrypt = new_magic_crypt!(password, 256);
+        let decrypted = mcrypt
+            .decrypt_base64_to_bytes(encrypted_mnemonic)
+            .map(Zeroizing::new);
+        Ok(decrypted.is_ok())
+    } else {
+        Err(APIError::NotInitialized)
+    }
+}
+
 pub(crate) fn check_channel_id(channel_id_str: &str) -> Result<ChannelId, APIError> {
     if let Some(channel_id_bytes) = hex_str_to_vec(channel_id_str) {
         if channel_id_bytes.len() != 32 {
@@ -209,27 +240,56 @@ pub(crate) fn check_port_is_available(port: u16) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Join a single file name to the given base directory, rejecting any component (e.g. one
+/// containing path separators or `..`) that could escape it.
+pub(crate) fn safe_join(base: &Path, component: &str) -> Result<PathBuf, APIError> {
+    let mut components = Path::new(component).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(name)), None) if !component.contains(['/', '\\']) => {
+            Ok(base.join(name))
+        }
+        _ => Err(APIError::InvalidPath(component.to_string())),
+    }
+}
+
 pub(crate) fn get_mnemonic_path(storage_dir_path: &Path) -> PathBuf {
     storage_dir_path.join("mnemonic")
 }
 
 pub(crate) fn encrypt_and_save_mnemonic(
     password: String,
-    mnemonic: String,
+    mnemonic: &str,
     mnemonic_path: &Path,
 ) -> Result<(), APIError> {
+    use std::io::Write as _;
+
     let mcrypt = new_magic_crypt!(password, 256);
     let encrypted_mnemonic = mcrypt.encrypt_str_to_base64(mnemonic);
-    match fs::write(mnemonic_path, encrypted_mnemonic) {
-        Ok(()) => {
-            tracing::info!("Created a new wallet");
-            Ok(())
+    // a full disk is reported as such, other write errors as a failed keys creation
+    let failed_creation = |e: std::io::Error| match map_write_error(e, mnemonic_path) {
+        APIError::IO(e) => {
+            APIError::FailedKeysCreation(mnemonic_path.to_string_lossy().to_string(), e.to_string())
         }
-        Err(e) => Err(APIError::FailedKeysCreation(
-            mnemonic_path.to_string_lossy().to_string(),
-            e.to_string(),
-        )),
-    }
+        e => e,
+    };
+
+    // losing the mnemonic means losing the wallet, so make sure it's on stable storage before
+    // returning: write and sync a temp file, atomically move it in place, then sync the directory
+    let dir = mnemonic_path.parent().expect("parent defined");
+    let mut tmp = NamedTempFile::new_in(dir).map_err(failed_creation)?;
+    tmp.as_file_mut()
+        .write_all(encrypted_mnemonic.as_bytes())
+        .and_then(|_| tmp.as_file().sync_all())
+        .map_err(failed_creation)?;
+    tmp.persist(mnemonic_path)
+        .map_err(|persist_err| failed_creation(persist_err.error))?;
+    #[cfg(unix)]
+    fs::File::open(dir)
+        .and_then(|d| d.sync_all())
+        .map_err(failed_creation)?;
+
+    tracing::info!("Created a new wallet");
+    Ok(())
 }
 
 pub(crate) async fn connect_peer_if_necessary(
@@ -320,20 +380,14 @@ where
     rx.await.unwrap()
 }
 
-pub(crate) fn parse_peer_info(
+pub(crate) async fn parse_peer_info(
     peer_pubkey_and_ip_addr: String,
 ) -> Result<(PublicKey, Option<SocketAddr>), APIError> {
     let mut pubkey_and_addr = peer_pubkey_and_ip_addr.split('@');
     let pubkey = pubkey_and_addr.next();
 
     let peer_addr = if let Some(peer_addr_str) = pubkey_and_addr.next() {
-        let peer_addr = peer_addr_str.to_socket_addrs().map(|mut r| r.next());
-        if peer_addr.is_err() || peer_addr.as_ref().unwrap().is_none() {
-            return Err(APIError::InvalidPeerInfo(s!(
-                "couldn't parse pubkey@host:port into a socket address"
-            )));
-        }
-        peer_addr.unwrap()
+        Some(resolve_peer_addr(peer_addr_str).await?)
     } else {
         None
     };
@@ -348,10 +402,80 @@ pub(crate) fn parse_peer_info(
     Ok((pubkey.unwrap(), peer_addr))
 }
 
-pub(crate) async fn start_daemon(args: &UserArgs) -> Result<Arc<AppState>, AppError> {
+/// Exclusive lock on the storage directory, preventing two node processes from sharing it.
+///
+/// It must be acquired before any other file in the storage directory is created or opened. The
+/// lock is released when the guard is dropped.
+pub(crate) struct LockGuard {
+    _file: fs::File,
+}
+
+impl LockGuard {
+    pub(crate) fn acquire(storage_dir_path: &Path) -> Result<Self, AppError> {
+        fs::create_dir_all(storage_dir_path)?;
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(storage_dir_path.join(NODE_LOCK_FNAME))?;
+        match file.try_lock_exclusive() {
+            Ok(()) => Ok(Self { _file: file }),
+            Err(e) if e.kind() == fs2::lock_contended_error().kind() => {
+                Err(AppError::DataDirInUse(storage_dir_path.to_path_buf()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Parse a host:port peer address, resolving the host if it's not an IP literal.
+pub(crate) async fn resolve_peer_addr(peer_addr_str: &str) -> Result<SocketAddr, APIError> {
+    if let Ok(peer_addr) = SocketAddr::from_str(peer_addr_str) {
+        return Ok(peer_addr);
+    }
+    match peer_addr_str.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => {
+            tokio::net::lookup_host(peer_addr_str)
+                .await
+                .ok()
+                .and_then(|mut addrs| addrs.next())
+                .ok_or_else(|| APIError::PeerHostUnresolvable(host.to_string()))
+        }
+        _ => Err(APIError::InvalidPeerInfo(s!(
+            "couldn't parse pubkey@host:port into a socket address"
+        ))),
+    }
+}
+
+/// Resolve a stored peer address, looking up hostnames without blocking the runtime.
+pub(crate) async fn resolve_socket_address(
+    address: &SocketAddress,
+) -> Result<Vec<SocketAddr>, APIError> {
+    match address {
+        SocketAddress::Hostname { hostname, port } => {
+            let host = hostname.as_str().to_string();
+            tokio::net::lookup_host((host.as_str(), *port))
+                .await
+                .map(|addrs| addrs.collect())
+                .map_err(|_| APIError::PeerHostUnresolvable(host))
+        }
+        // IP addresses don't need a lookup, onion addresses can't be resolved
+        _ => Ok(address.to_socket_addrs()?.collect()),
+    }
+}
+
+pub(crate) async fn start_daemon(
+    args: &UserArgs,
+    storage_dir_lock: LockGuard,
+) -> Result<Arc<AppState>, AppError> {
     // Initialize the Logger (creates ldk_data_dir and its logs directory)
     let ldk_data_dir = args.storage_dir_path.join(LDK_DIR);
-    let logger = Arc::new(FilesystemLogger::new(ldk_data_dir.clone()));
+    let logger = Arc::new(if let Some(logs_dir) = &args.logs_dir {
+        fs::create_dir_all(&ldk_data_dir)?;
+        FilesystemLogger::new_with_logs_dir(logs_dir.clone(), args.max_log_line_bytes)?
+    } else {
+        FilesystemLogger::new(ldk_data_dir.clone(), args.max_log_line_bytes)?
+    });
 
     let cancel_token = CancellationToken::new();
 
@@ -362,6 +486,7 @@ pub(crate) async fn start_daemon(args: &UserArgs) -> Result<Arc<AppState>, AppEr
         ldk_data_dir,
         logger,
         max_media_upload_size_mb: args.max_media_upload_size_mb,
+        env_prefix: args.env_prefix.clone(),
     });
 
     let app_state = Arc::new(AppState {
@@ -372,6 +497,7 @@ pub(crate) async fn start_daemon(args: &UserArgs) -> Result<Arc<AppState>, AppEr
         changing_state: Mutex::new(false),
         root_public_key: args.root_public_key,
         revoked_tokens: Arc::new(Mutex::new(HashSet::new())),
+        storage_dir_lock: Mutex::new(Some(storage_dir_lock)),
     });
 
     // Load revoked tokens from file if authentication is enabled
@@ -390,6 +516,78 @@ pub(crate) fn get_current_timestamp() -> u64 {
         .as_secs()
 }
 
+/// Return the provided value or, if missing, the non-empty value of the env variable named as
+/// the given key with the given prefix (e.g. `RLN_` + `INDEXER_URL`).
+pub(crate) fn value_or_env(value: Option<String>, env_prefix: &str, key: &str) -> Option<String> {
+    value_or_env_with(value, env_prefix, key, |env_var| {
+        std::env::var(env_var).ok()
+    })
+}
+
+/// Same as [`value_or_env`], looking up env variables with the given function.
+pub(crate) fn value_or_env_with(
+    value: Option<String>,
+    env_prefix: &str,
+    key: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    value.or_else(|| {
+        let env_var = format!("{env_prefix}{key}");
+        let env_value = lookup(&env_var).filter(|v| !v.trim().is_empty())?;
+        tracing::info!("Using {env_var} from the environment");
+        Some(env_value)
+    })
+}
+
+pub(crate) fn default_indexer_url(network: BitcoinNetwork) -> &'static str {
+    match network {
+        BitcoinNetwork::Regtest => ELECTRUM_URL_REGTEST,
+        BitcoinNetwork::Signet => ELECTRUM_URL_SIGNET,
+        BitcoinNetwork::Testnet => ELECTRUM_URL_TESTNET,
+        BitcoinNetwork::Testnet4 => ELECTRUM_URL_TESTNET4,
+        BitcoinNetwork::Mainnet => ELECTRUM_URL_MAINNET,
+    }
+}
+
+pub(crate) fn default_proxy_endpoint(network: BitcoinNetwork) -> &'static str {
+    match network {
+        BitcoinNetwork::Signet
+        | BitcoinNetwork::Testnet
+        | BitcoinNetwork::Testnet4
+        | BitcoinNetwork::Mainnet => PROXY_ENDPOINT_PUBLIC,
+        BitcoinNetwork::Regtest => PROXY_ENDPOINT_LOCAL,
+    }
+}
+
+/// Return the network of the default indexer matching the given URL, if any.
+pub(crate) fn default_indexer_network(indexer_url: &str) -> Option<BitcoinNetwork> {
+    [
+        BitcoinNetwork::Regtest,
+        BitcoinNetwork::Signet,
+        BitcoinNetwork::Testnet,
+        BitcoinNetwork::Testnet4,
+        BitcoinNetwork::Mainnet,
+    ]
+    .into_iter()
+    .find(|n| default_indexer_url(*n) == indexer_url.trim())
+}
+
+/// Warn if the given indexer URL is the default one of a different network.
+///
+/// Returns whether a mismatch has been detected.
+pub(crate) fn warn_indexer_network_mismatch(indexer_url: &str, network: BitcoinNetwork) -> bool {
+    match default_indexer_network(indexer_url) {
+        Some(indexer_network) if indexer_network != network => {
+            tracing::warn!(
+                "Indexer {indexer_url} is the default one for {indexer_network} but the node is \
+                 configured for {network}"
+            );
+            true
+        }
+        _ => false,
+    }
+}
+
 pub(crate) fn get_max_local_rgb_amount<'r>(
     contract_id: ContractId,
     ldk_data_dir_path: &Path,
@@ -479,3 +677,121 @@ pub(crate) fn validate_and_parse_payment_preimage(
     }
     Ok(preimage)
 }
+
+pub(crate) fn validate_and_parse_announce_alias(alias: &str) -> Result<[u8; 32], APIError> {
+    if alias.len() > 32 {
+        return Err(APIError::InvalidAnnounceAlias(s!(
+            "cannot be longer than 32 bytes"
+        )));
+    }
+    let mut bytes = [0; 32];
+    bytes[..alias.len()].copy_from_slice(alias.as_bytes());
+    Ok(bytes)
+}
+
+pub(crate) fn validate_and_parse_announce_color(color: &str) -> Result<[u8; 3], APIError> {
+    match hex_str_to_vec(color) {
+        Some(color_vec) if color.len() == 6 => Ok(color_vec.try_into().unwrap()),
+        _ => Err(APIError::InvalidAnnounceColor(s!(
+            "must be a 6 hex digits RGB color"
+        ))),
+    }
+}
+
+fn rgb_payment_info(
+    payment_hash: &PaymentHash,
+    ldk_data_dir: &Path,
+    inbound: bool,
+) -> (Option<u64>, Option<String>) {
+    let rgb_payment_info_path = get_rgb_payment_info_path(payment_hash, ldk_data_dir, inbound);
+    if rgb_payment_info_path.exists() {
+        let info = parse_rgb_payment_info(&rgb_payment_info_path);
+        (Some(info.amount), Some(info.contract_id.to_string()))
+    } else {
+        (None, None)
+    }
+}
+
+pub(crate) fn inbound_payment(
+    payment_hash: &PaymentHash,
+    payment_info: &PaymentInfo,
+    ldk_data_dir: &Path,
+) -> Payment {
+    let (asset_amount, asset_id) = rgb_payment_info(payment_hash, ldk_data_dir, true);
+    Payment {
+        amt_msat: payment_info.amt_msat,
+        asset_amount,
+        asset_id,
+        payment_hash: hex_str(&payment_hash.0),
+        inbound: true,
+        status: payment_info.status,
+        created_at: payment_info.created_at,
+        updated_at: payment_info.updated_at,
+        payee_pubkey: payment_info.payee_pubkey.to_string(),
+        preimage: None,
+    }
+}
+
+pub(crate) fn outbound_payment(
+    payment_id: &PaymentId,
+    payment_info: &PaymentInfo,
+    ldk_data_dir: &Path,
+) -> Payment {
+    let payment_hash = PaymentHash(payment_id.0);
+    let (asset_amount, asset_id) = rgb_payment_info(&payment_hash, ldk_data_dir, false);
+    let preimage = if matches!(payment_info.status, HTLCStatus::Succeeded) {
+        payment_info.preimage.map(|p| hex_str(&p.0))
+    } else {
+        None
+    };
+    Payment {
+        amt_msat: payment_info.amt_msat,
+        asset_amount,
+        asset_id,
+        payment_hash: hex_str(&payment_hash.0),
+        inbound: false,
+        status: payment_info.status,
+        created_at: payment_info.created_at,
+        updated_at: payment_info.updated_at,
+        payee_pubkey: payment_info.payee_pubkey.to_string(),
+        preimage,
+    }
+}
+
+const PAYMENTS_CSV_HEADER: &str = "payment_hash,inbound,status,amt_msat,asset_amount,asset_id,\
+    payee_pubkey,preimage,created_at,updated_at";
+
+/// Quote a CSV field if it contains a separator, a quote or a line break.
+fn csv_field(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
+    }
+}
+
+/// CSV rows for the given payments after a header row, each one ending with a line break.
+///
+/// Rows are built lazily, one payment at a time, so they can be streamed as they are produced.
+pub(crate) fn payments_csv_rows(
+    payments: impl IntoIterator<Item = Payment>,
+) -> impl Iterator<Item = String> {
+    fn opt(value: Option<String>) -> String {
+        value.unwrap_or_default()
+    }
+    std::iter::once(format!("{PAYMENTS_CSV_HEADER}\n")).chain(payments.into_iter().map(|payment| {
+        format!(
+            "{},{},{:?},{},{},{},{},{},{},{}\n",
+            csv_field(&payment.payment_hash),
+            payment.inbound,
+            payment.status,
+            opt(payment.amt_msat.map(|a| a.to_string())),
+            opt(payment.asset_amount.map(|a| a.to_string())),
+            csv_field(&opt(payment.asset_id)),
+            csv_field(&payment.payee_pubkey),
+            csv_field(&opt(payment.preimage)),
+            payment.created_at,
+            payment.updated_at,
+        )
+    }))
+}